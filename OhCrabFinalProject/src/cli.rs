@@ -0,0 +1,139 @@
+use clap::{Args, Parser, Subcommand};
+use oxagworldgenerator::world_generator::OxAgWorldGenerator;
+use visualizer::{oh_crab_visualizer::visualizer::{visualizable_robot::RobotCreator, robot_registry::{RobotRegistry, RobotParams}, visualizer::OhCrabVisualizerError}, world_gen_utils};
+
+#[derive(Parser, Debug)]
+#[command(name = "visualizer", about = "Runs OhCrab robots with or without the visualizer")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Runs the robot in the visualizer window
+    Visualize {
+        #[command(flatten)]
+        world: WorldArgs,
+        #[command(flatten)]
+        robot: RobotArgs,
+        /// Number of ticks done automatically, the run is interactive when not given
+        #[arg(long)]
+        ticks: Option<usize>,
+        /// Plays sounds of robot events
+        #[arg(long)]
+        sound: bool,
+        /// TOML or JSON settings file, its run mode and sound are overridden by the options
+        #[arg(long)]
+        settings: Option<String>,
+        /// Records the simulation to the given file
+        #[arg(long)]
+        record: Option<String>,
+        /// Writes structured JSON lines log of all events to the given file
+        #[arg(long)]
+        event_log: Option<String>,
+        /// Streams the simulation as JSON lines to clients connected to the given local address, e.g. 127.0.0.1:7878
+        #[arg(long)]
+        stream: Option<String>,
+    },
+    /// Runs the robot without the visualizer
+    Simulate {
+        #[command(flatten)]
+        world: WorldArgs,
+        #[command(flatten)]
+        robot: RobotArgs,
+        #[arg(long, default_value_t = 500)]
+        ticks: usize,
+        /// Records the simulation to the given file
+        #[arg(long)]
+        record: Option<String>,
+        /// Writes structured JSON lines log of all events to the given file
+        #[arg(long)]
+        event_log: Option<String>,
+        /// Streams the simulation as JSON lines to clients connected to the given local address, e.g. 127.0.0.1:7878
+        #[arg(long)]
+        stream: Option<String>,
+    },
+    /// Runs the robot headlessly in every given world and writes report of the runs
+    Batch {
+        #[command(flatten)]
+        robot: RobotArgs,
+        /// World as size:seed, can be repeated
+        #[arg(long = "world", value_parser = parse_world, required = true)]
+        worlds: Vec<(usize, u64)>,
+        #[arg(long, default_value_t = 500)]
+        ticks: usize,
+        /// Report file, written as CSV when it ends with `.csv`, otherwise as JSON
+        #[arg(long, default_value = "reports/batch.json")]
+        report: String,
+    },
+    /// Lists robots that can be selected by `--robot` and their parameters
+    ListRobots,
+    /// Generates world and saves it to `generated_worlds`
+    GenerateWorld {
+        #[arg(long)]
+        size: usize,
+        #[arg(long)]
+        seed: u64,
+    },
+    /// Solves the partitioning problem of the distributor robot for weights from the file
+    Partition {
+        /// File with one weight per line
+        #[arg(long, default_value = "test_data/partition.txt")]
+        weights: String,
+        /// Number of piles (markets)
+        #[arg(long, default_value_t = 10)]
+        piles: usize,
+        #[arg(long, default_value = "logs/evolutionary_algo_test.log")]
+        log: String,
+    },
+    /// Runs one of the examples, e.g. `distribution-small` or `replay`
+    Example {
+        name: String,
+    },
+}
+
+/// World is loaded from `--world-file` if given, otherwise it is loaded from `generated_worlds` or generated
+#[derive(Args, Debug)]
+pub struct WorldArgs {
+    #[arg(long, default_value_t = 40)]
+    pub size: usize,
+    #[arg(long, default_value_t = 420)]
+    pub seed: u64,
+    #[arg(long)]
+    pub world_file: Option<String>,
+}
+
+/// Robot is created by the robot registry, see `list-robots` for the robots and their parameters
+#[derive(Args, Debug)]
+pub struct RobotArgs {
+    #[arg(long, default_value = "distributor")]
+    pub robot: String,
+    /// Robot parameter as key=value, can be repeated
+    #[arg(long = "param")]
+    pub params: Vec<String>,
+}
+
+impl WorldArgs {
+    pub fn world_generator(&self) -> Result<OxAgWorldGenerator, OhCrabVisualizerError> {
+        match &self.world_file {
+            Some(world_file) => world_gen_utils::load_world_from_file(world_file),
+            None => Ok(world_gen_utils::load_or_generate_world(self.size, self.seed)),
+        }
+    }
+}
+
+impl RobotArgs {
+    pub fn robot_creator(&self, registry: &RobotRegistry) -> Result<Box<dyn RobotCreator>, OhCrabVisualizerError> {
+        let params = RobotParams::parse(&self.params)?;
+        registry.create(&self.robot, &params)
+    }
+}
+
+/// Parses world given as `size:seed`
+pub fn parse_world(world: &str) -> Result<(usize, u64), String> {
+    let (size, seed) = world.split_once(':').ok_or_else(|| format!("World {} is not in size:seed format", world))?;
+    let size = size.parse().map_err(|_| format!("Invalid world size {}", size))?;
+    let seed = seed.parse().map_err(|_| format!("Invalid world seed {}", seed))?;
+    Ok((size, seed))
+}
@@ -1,4 +1,5 @@
 pub mod world_gen_utils;
 pub mod oh_crab_visualizer;
 pub mod robot_veronika;
-pub mod robot_tanya;
\ No newline at end of file
+pub mod robot_tanya;
+pub mod robots;
\ No newline at end of file
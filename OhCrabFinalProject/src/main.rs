@@ -1,8 +1,19 @@
-use std::env;
+use clap::Parser;
+use oxagworldgenerator::world_generator::OxAgWorldGenerator;
+use visualizer::{oh_crab_visualizer::visualizer::{visualizer::{OhCrabVisualizer, OhCrabVisualizerConfig, OhCrabVisualizerError, RunMode as VisualizerRunMode}, settings::VisualizerSettings, batch_simulation::BatchSimulation, visualizable_robot::RobotCreator, robot_registry::RobotRegistry},
+                 robot_veronika::partitioning::PartitioningProblem, robots::builtin_robot_registry, world_gen_utils};
 
 use visualizer::oh_crab_visualizer::examples::example::{visualizer_nonteractive, visualizer_interactive, visualizer_smaller,
-                                                        distribution_big_simulate, distribution_bigger_viz, distribution_small_viz};
+                                                        distribution_big_simulate, distribution_bigger_viz, distribution_small_viz,
+                                                        distribution_record, visualizer_replay, multi_robot_viz,
+                                                        distribution_batch, distribution_frames, distribution_from_settings,
+                                                        distribution_headless};
 
+use cli::{Cli, Command, RobotArgs, WorldArgs};
+
+mod cli;
+
+/// Examples run by the `example` subcommand
 #[derive(Debug)]
 enum RunMode {
     VisualizerInteractive,
@@ -11,7 +22,13 @@ enum RunMode {
     DistributionSmall,
     DistributionBig,
     DistributionSimulation,
-    Tanya,
+    DistributionRecord,
+    Replay,
+    MultiRobot,
+    DistributionBatch,
+    DistributionFrames,
+    DistributionSettings,
+    DistributionHeadless,
 }
 
 impl RunMode {
@@ -23,21 +40,124 @@ impl RunMode {
             "distribution-small" => Some(RunMode::DistributionSmall),
             "distribution-big" => Some(RunMode::DistributionBig),
             "distribution-simulation" => Some(RunMode::DistributionSimulation),
-            "tanya" => Some(RunMode::Tanya),
+            "distribution-record" => Some(RunMode::DistributionRecord),
+            "replay" => Some(RunMode::Replay),
+            "multi-robot" => Some(RunMode::MultiRobot),
+            "distribution-batch" => Some(RunMode::DistributionBatch),
+            "distribution-frames" => Some(RunMode::DistributionFrames),
+            "distribution-settings" => Some(RunMode::DistributionSettings),
+            "distribution-headless" => Some(RunMode::DistributionHeadless),
             _ => None,
         }
     }
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let cli = Cli::parse();
+    let registry = builtin_robot_registry();
+    match cli.command {
+        Command::Visualize { world, robot, ticks, sound, settings, record, event_log, stream } => {
+            let run_mode = match ticks {
+                Some(ticks) => VisualizerRunMode::NonInteractive(ticks),
+                None => VisualizerRunMode::Interactive,
+            };
+            let mut config = OhCrabVisualizerConfig::new(run_mode, sound);
+            if let Some(settings_path) = settings {
+                match VisualizerSettings::load(&settings_path) {
+                    Ok(settings) => config = config.with_settings(settings),
+                    Err(err) => {
+                        eprintln!("Loading settings returned error {:?}", err);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            if let Some(recording_path) = record {
+                config = config.with_recording(&recording_path);
+            }
+            if let Some(event_log_path) = event_log {
+                config = config.with_event_log(&event_log_path);
+            }
+            if let Some(stream_address) = stream {
+                config = config.with_live_stream(&stream_address);
+            }
+            let visualizer = create_visualizer(OhCrabVisualizer::new(create_robot(&robot, &registry), create_world(&world), config));
+            match visualizer.run() {
+                Ok(_) => {}
+                Err(err) => println!("Visualizer run returned error {:?}", err),
+            }
+        }
+        Command::Simulate { world, robot, ticks, record, event_log, stream } => {
+            let mut config = OhCrabVisualizerConfig::new(VisualizerRunMode::NonInteractive(ticks), false);
+            if let Some(recording_path) = record {
+                config = config.with_recording(&recording_path);
+            }
+            if let Some(event_log_path) = event_log {
+                config = config.with_event_log(&event_log_path);
+            }
+            if let Some(stream_address) = stream {
+                config = config.with_live_stream(&stream_address);
+            }
+            let mut visualizer = create_visualizer(OhCrabVisualizer::new(create_robot(&robot, &registry), create_world(&world), config));
+            match visualizer.simulate() {
+                Ok(_) => {}
+                Err(err) => println!("Visualizer simulate returned error {:?}", err),
+            }
+        }
+        Command::Batch { robot, worlds, ticks, report } => {
+            let batch = BatchSimulation::new(create_robot(&robot, &registry), worlds, ticks);
+            let batch_report = batch.run();
+            let write_result = if report.ends_with(".csv") { batch_report.write_csv(&report) } else { batch_report.write_json(&report) };
+            match write_result {
+                Ok(_) => {}
+                Err(err) => println!("Writing report returned error {:?}", err),
+            }
+        }
+        Command::ListRobots => print!("{}", registry.describe()),
+        Command::GenerateWorld { size, seed } => {
+            world_gen_utils::generate_and_save_world(size, seed);
+            println!("World size {} seed {} saved to generated_worlds", size, seed);
+        }
+        Command::Partition { weights, piles, log } => {
+            let mut problem = PartitioningProblem::with_default_params(Vec::new(), piles);
+            problem.set_weights_from_file(&weights);
+            let best_solution: Vec<usize> = problem.main_exec(&log);
+            println!("Pile of each weight: {:?}", best_solution);
+        }
+        Command::Example { name } => run_example(&name),
+    }
+}
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <mode>", args[0]);
-        std::process::exit(1);
+fn create_robot(robot: &RobotArgs, registry: &RobotRegistry) -> Box<dyn RobotCreator> {
+    match robot.robot_creator(registry) {
+        Ok(robot_creator) => robot_creator,
+        Err(err) => {
+            eprintln!("Creating robot returned error {:?}", err);
+            std::process::exit(1);
+        }
     }
+}
 
-    let input_name = &args[1];
+fn create_world(world: &WorldArgs) -> OxAgWorldGenerator {
+    match world.world_generator() {
+        Ok(world_generator) => world_generator,
+        Err(err) => {
+            eprintln!("Loading world returned error {:?}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn create_visualizer(visualizer: Result<OhCrabVisualizer, OhCrabVisualizerError>) -> OhCrabVisualizer {
+    match visualizer {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            eprintln!("Creating visualizer returned error {:?}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_example(input_name: &str) {
     match RunMode::from_str(input_name) {
         Some(RunMode::VisualizerInteractive) => {
             visualizer_interactive();
@@ -57,10 +177,27 @@ fn main() {
         Some(RunMode::DistributionSimulation) => {
             distribution_big_simulate();
         }
-        Some(RunMode::Tanya) => {
-            println!("Run your example here.")
+        Some(RunMode::DistributionRecord) => {
+            distribution_record();
+        }
+        Some(RunMode::Replay) => {
+            visualizer_replay();
+        }
+        Some(RunMode::MultiRobot) => {
+            multi_robot_viz();
+        }
+        Some(RunMode::DistributionBatch) => {
+            distribution_batch();
+        }
+        Some(RunMode::DistributionFrames) => {
+            distribution_frames();
+        }
+        Some(RunMode::DistributionSettings) => {
+            distribution_from_settings();
+        }
+        Some(RunMode::DistributionHeadless) => {
+            distribution_headless();
         }
         None => eprintln!("Invalid name: {}", input_name),
     }
-    //example();
 }
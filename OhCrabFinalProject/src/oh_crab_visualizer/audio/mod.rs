@@ -6,34 +6,38 @@ use oxagaudiotool::sound_config::OxAgSoundConfig;
 use oxagaudiotool::OxAgAudioTool;
 use oxagaudiotool::error::error::OxAgAudioToolError;
 
-pub fn get_configured_audio_tool() -> Result<OxAgAudioTool, OxAgAudioToolError>  {
+use crate::oh_crab_visualizer::visualizer::settings::{AssetSettings, SoundSettings};
+
+pub fn get_configured_audio_tool(assets: &AssetSettings, sound: &SoundSettings) -> Result<OxAgAudioTool, OxAgAudioToolError>  {
     println!("Loading sounds...");
+    let audio_path = assets.audio_path();
+    let audio_file = |file_name: &str| format!("{}/{}", audio_path, file_name);
 
     let mut events = HashMap::new();
-    events.insert(Event::Ready, OxAgSoundConfig::new("assets/audio/event/event_ready.ogg"));
-    events.insert(Event::Terminated, OxAgSoundConfig::new("assets/audio/event/event_terminated.ogg"));
-    events.insert(Event::EnergyRecharged(0), OxAgSoundConfig::new_with_volume("assets/audio/event/event_energy_recharged.ogg", 0.1));
-    events.insert(Event::AddedToBackpack(Content::None, 0), OxAgSoundConfig::new("assets/audio/event/event_add_to_backpack.ogg"));
-    events.insert(Event::RemovedFromBackpack(Content::None, 0), OxAgSoundConfig::new("assets/audio/event/event_remove_from_backpack.ogg"));
+    events.insert(Event::Ready, OxAgSoundConfig::new_with_volume(&audio_file("event/event_ready.ogg"), sound.event_volume));
+    events.insert(Event::Terminated, OxAgSoundConfig::new_with_volume(&audio_file("event/event_terminated.ogg"), sound.event_volume));
+    events.insert(Event::EnergyRecharged(0), OxAgSoundConfig::new_with_volume(&audio_file("event/event_energy_recharged.ogg"), sound.energy_recharged_volume));
+    events.insert(Event::AddedToBackpack(Content::None, 0), OxAgSoundConfig::new_with_volume(&audio_file("event/event_add_to_backpack.ogg"), sound.event_volume));
+    events.insert(Event::RemovedFromBackpack(Content::None, 0), OxAgSoundConfig::new_with_volume(&audio_file("event/event_remove_from_backpack.ogg"), sound.event_volume));
 
     let mut tiles = HashMap::new();
-    tiles.insert(TileType::DeepWater, OxAgSoundConfig::new("assets/audio/tile/tile_water.ogg"));
-    tiles.insert(TileType::ShallowWater, OxAgSoundConfig::new("assets/audio/tile/tile_water.ogg"));
-    tiles.insert(TileType::Sand, OxAgSoundConfig::new("assets/audio/tile/tile_sand.ogg"));
-    tiles.insert(TileType::Grass, OxAgSoundConfig::new("assets/audio/tile/tile_grass.ogg"));
-    tiles.insert(TileType::Hill, OxAgSoundConfig::new("assets/audio/tile/tile_grass.ogg"));
-    tiles.insert(TileType::Mountain, OxAgSoundConfig::new("assets/audio/tile/tile_mountain.ogg"));
-    tiles.insert(TileType::Snow, OxAgSoundConfig::new("assets/audio/tile/tile_snow.ogg"));
-    tiles.insert(TileType::Lava, OxAgSoundConfig::new("assets/audio/tile/tile_lava.ogg"));
-    tiles.insert(TileType::Teleport(false), OxAgSoundConfig::new("assets/audio/tile/tile_teleport.ogg"));
-    tiles.insert(TileType::Street, OxAgSoundConfig::new("assets/audio/tile/tile_street.ogg"));
+    tiles.insert(TileType::DeepWater, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_water.ogg"), sound.tile_volume));
+    tiles.insert(TileType::ShallowWater, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_water.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Sand, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_sand.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Grass, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_grass.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Hill, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_grass.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Mountain, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_mountain.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Snow, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_snow.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Lava, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_lava.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Teleport(false), OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_teleport.ogg"), sound.tile_volume));
+    tiles.insert(TileType::Street, OxAgSoundConfig::new_with_volume(&audio_file("tile/tile_street.ogg"), sound.tile_volume));
 
     let mut weather = HashMap::new();
-    weather.insert(WeatherType::Rainy, OxAgSoundConfig::new("assets/audio/weather/weather_rainy.ogg"));
-    weather.insert(WeatherType::Foggy, OxAgSoundConfig::new("assets/audio/weather/weather_foggy.ogg"));
-    weather.insert(WeatherType::Sunny, OxAgSoundConfig::new("assets/audio/weather/weather_sunny.ogg"));
-    weather.insert(WeatherType::TrentinoSnow, OxAgSoundConfig::new("assets/audio/weather/weather_winter.ogg"));
-    weather.insert(WeatherType::TropicalMonsoon, OxAgSoundConfig::new("assets/audio/weather/weather_tropical.ogg"));
+    weather.insert(WeatherType::Rainy, OxAgSoundConfig::new_with_volume(&audio_file("weather/weather_rainy.ogg"), sound.weather_volume));
+    weather.insert(WeatherType::Foggy, OxAgSoundConfig::new_with_volume(&audio_file("weather/weather_foggy.ogg"), sound.weather_volume));
+    weather.insert(WeatherType::Sunny, OxAgSoundConfig::new_with_volume(&audio_file("weather/weather_sunny.ogg"), sound.weather_volume));
+    weather.insert(WeatherType::TrentinoSnow, OxAgSoundConfig::new_with_volume(&audio_file("weather/weather_winter.ogg"), sound.weather_volume));
+    weather.insert(WeatherType::TropicalMonsoon, OxAgSoundConfig::new_with_volume(&audio_file("weather/weather_tropical.ogg"), sound.weather_volume));
 
     // Create the audio tool
     OxAgAudioTool::new(events, tiles, weather)
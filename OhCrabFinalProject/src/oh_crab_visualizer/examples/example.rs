@@ -1,15 +1,28 @@
-use crate::oh_crab_visualizer::visualizer::visualizer::{OhCrabVisualizerConfig, OhCrabVisualizer, RunMode};
-use robotics_lib::world::tile::Content;
+use crate::oh_crab_visualizer::visualizer::{visualizer::{OhCrabVisualizerConfig, OhCrabVisualizer, RunMode}, visualizable_robot::RobotCreator, batch_simulation::BatchSimulation, frame_export::FrameExportConfig,
+                                           visualizer_event_listener::VisualizerEventListener, event_sink::{JsonLinesSink, MetricsSink}};
+use robotics_lib::{runner::Runner, world::tile::Content};
 use super::example_robot::ExampleRobotFactory;
 use crate::robot_veronika::distribution_robot::DistributorRobotFactory;
 
+const RECORDING_PATH: &str = "./recordings/distribution_size40_seed420.jsonl";
+const REPORT_PATH: &str = "./reports/distribution_batch";
+const FRAMES_PATH: &str = "./frames/distribution";
+const SETTINGS_PATH: &str = "./visualizer_settings.toml";
+const EVENT_LOG_PATH: &str = "./logs/distribution_events.jsonl";
+
 pub fn visualizer_nonteractive(){
     let robot_factory = ExampleRobotFactory::new(42);
     let world_generator = crate::world_gen_utils::load_or_generate_world(256, 420);
     //let world_generator = crate::world_gen_utils::load_or_generate_world(15, 42);
 
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(400), true);
-    let visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     //visualizer.simulate().unwrap();
     match visualizer.run() {
@@ -24,7 +37,13 @@ pub fn visualizer_interactive(){
     //let world_generator = crate::world_gen_utils::load_or_generate_world(15, 42);
 
     let config = OhCrabVisualizerConfig::new(RunMode::Interactive, true);
-    let visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
     
     //visualizer.simulate().unwrap();
     match visualizer.run() {
@@ -38,7 +57,85 @@ pub fn visualizer_smaller(){
     let world_generator = crate::world_gen_utils::load_or_generate_world(128, 420);
 
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(400), false);
-    let visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
+
+    match visualizer.run() {
+        Ok(_) => {}
+        Err(err) => println!("Visualizer run returned error {:?}", err),
+    }
+}
+
+pub fn distribution_record(){
+    let robot_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let world_generator = crate::world_gen_utils::load_or_generate_world(40, 420);
+
+    let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), false).with_recording(RECORDING_PATH);
+    let mut visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
+
+    match visualizer.simulate() {
+        Ok(_) => {}
+        Err(err) => println!("Visualizer simulate returned error {:?}", err),
+    }
+}
+
+pub fn visualizer_replay(){
+    let config = OhCrabVisualizerConfig::new(RunMode::Interactive, false);
+
+    match OhCrabVisualizer::replay(RECORDING_PATH, config) {
+        Ok(visualizer) => {
+            match visualizer.run() {
+                Ok(_) => {}
+                Err(err) => println!("Visualizer run returned error {:?}", err),
+            }
+        }
+        Err(err) => println!("Loading recording returned error {:?}", err),
+    }
+}
+
+pub fn distribution_batch(){
+    let robot_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let batch = BatchSimulation::new(robot_factory, vec![(20, 420), (40, 420), (40, 42)], 500);
+
+    let report = batch.run();
+    match report.write_json(&format!("{REPORT_PATH}.json")) {
+        Ok(_) => {}
+        Err(err) => println!("Writing JSON report returned error {:?}", err),
+    }
+    match report.write_csv(&format!("{REPORT_PATH}.csv")) {
+        Ok(_) => {}
+        Err(err) => println!("Writing CSV report returned error {:?}", err),
+    }
+}
+
+pub fn multi_robot_viz(){
+    let distributor_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let example_factory = ExampleRobotFactory::new(42);
+    let world_generator = crate::world_gen_utils::load_or_generate_world(40, 420);
+
+    let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), false);
+    let robot_factories: Vec<(String, Box<dyn RobotCreator>)> = vec![
+        ("Distributor".to_owned(), Box::new(distributor_factory) as Box<dyn RobotCreator>),
+        ("Example".to_owned(), Box::new(example_factory) as Box<dyn RobotCreator>),
+    ];
+    let visualizer = match OhCrabVisualizer::new_multi_robot(robot_factories, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     match visualizer.run() {
         Ok(_) => {}
@@ -52,7 +149,13 @@ pub fn distribution_small_viz(){
 
 
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), true);
-    let visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     match visualizer.run() {
         Ok(_) => {}
@@ -66,7 +169,13 @@ pub fn distribution_bigger_viz(){
 
 
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), false);
-    let visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     match visualizer.run() {
         Ok(_) => {}
@@ -80,7 +189,80 @@ pub fn distribution_big_simulate(){
 
 
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), false);
-    let mut visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let mut visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     visualizer.simulate().unwrap();
-}
\ No newline at end of file
+}
+
+pub fn distribution_frames(){
+    let robot_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let world_generator = crate::world_gen_utils::load_or_generate_world(40, 420);
+
+    let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(200), false);
+    let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
+    let export_config = FrameExportConfig::new(FRAMES_PATH)
+        .with_png_ticks(&[1, 100, 200])
+        .with_gif("run.gif");
+
+    match visualizer.export_frames(export_config) {
+        Ok(_) => {}
+        Err(err) => println!("Frame export returned error {:?}", err),
+    }
+}
+
+pub fn distribution_from_settings(){
+    let robot_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let world_generator = crate::world_gen_utils::load_or_generate_world(40, 420);
+
+    match OhCrabVisualizerConfig::from_settings_file(SETTINGS_PATH) {
+        Ok(config) => {
+            let visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+                Ok(visualizer) => visualizer,
+                Err(err) => {
+                    println!("Creating visualizer returned error {:?}", err);
+                    return;
+                }
+            };
+            match visualizer.run() {
+                Ok(_) => {}
+                Err(err) => println!("Visualizer run returned error {:?}", err),
+            }
+        }
+        Err(err) => println!("Loading settings returned error {:?}", err),
+    }
+}
+
+pub fn distribution_headless(){
+    let robot_factory = DistributorRobotFactory::new(vec![Content::Rock(1).index(), Content::Fish(1).index(), Content::Tree(1).index()]);
+    let mut world_generator = crate::world_gen_utils::load_or_generate_world(40, 420);
+
+    // robot runs directly in robotic lib runner, its events are only logged and counted
+    let metrics_sink = MetricsSink::new();
+    let mut event_listener = VisualizerEventListener::headless().with_sink(metrics_sink.clone());
+    match JsonLinesSink::new(EVENT_LOG_PATH) {
+        Ok(log_sink) => event_listener.add_sink(Box::new(log_sink)),
+        Err(err) => println!("Creating event log returned error {:?}", err),
+    }
+    let robot = robot_factory.create_forwarding(event_listener);
+
+    let mut runner = Runner::new(robot, &mut world_generator).expect("Runner creation failed");
+    for _ in 0..500 {
+        if let Err(err) = runner.game_tick() {
+            println!("Game tick returned error {:?}", err);
+            break;
+        }
+    }
+    println!("{:?}", metrics_sink.metrics());
+}
@@ -1,7 +1,7 @@
 use ohcrab_collection::collection::CollectTool;
 use robotics_lib::{runner::{Robot, Runnable}, interface::Direction, world::tile::Content};
 
-use crate::{oh_crab_visualizer::visualizer::{visualizable_interfaces::VisualizableInterfaces, visualizable_robot::{RobotCreator, Visulizable}, visualizer_event_listener::VisualizerEventListener}, println_d};
+use crate::{oh_crab_visualizer::visualizer::{visualizable_interfaces::VisualizableInterfaces, visualizable_robot::RobotCreator, visualizer_event_listener::VisualizerEventListener}, println_d, visulizable};
 
 pub struct ExampleRobot{
     properties: Robot,
@@ -27,11 +27,7 @@ impl RobotCreator for ExampleRobotFactory {
     }
 }
 
-impl<'a> Visulizable<'a> for ExampleRobot {
-    fn borrow_event_listener(&'a self) -> &'a VisualizerEventListener{
-        &self.visualizer_event_listener
-    }
-}
+visulizable!(ExampleRobot, visualizer_event_listener);
 
 impl ExampleRobot {
     fn int_to_direction(number: i32) -> Direction {
@@ -105,11 +101,8 @@ impl Runnable for ExampleRobot {
         // }
     }
 
-    fn handle_event(&mut self, event: robotics_lib::event::events::Event) {
-        println_d!("Example robot received event: {}", event);
-        // BEWARE - for a visualizer to work it is neccessary to call this method from 
-        // handle_event method of your robot
-        self.visualizer_event_listener.handle_event(&event); 
+    fn handle_event(&mut self, _event: robotics_lib::event::events::Event) {
+        // events are forwarded to the visualizer automatically by `EventForwardingRobot`
     }
 
     fn get_energy(&self) -> &robotics_lib::energy::Energy {
@@ -0,0 +1,41 @@
+use std::collections::VecDeque;
+
+use rstykrab_cache::Action;
+
+use super::visualizer_event_listener::InterfaceOutcome;
+
+/// One action displayed in the history window, actions inferred from events have no outcome
+///
+pub(super) struct HistoryRecord {
+    pub(super) action: Action,
+    pub(super) position: (usize, usize),    // (row, col)
+    pub(super) outcome: Option<InterfaceOutcome>
+}
+
+/// Last actions of the robot with the outcomes of their interface calls, the oldest action is dropped when the history is full
+///
+pub(super) struct ActionHistory {
+    records: VecDeque<HistoryRecord>,   // the most recent record is the last one
+    length: usize
+}
+
+impl ActionHistory {
+    pub(super) fn new(length: usize) -> ActionHistory {
+        ActionHistory { records: VecDeque::with_capacity(length), length }
+    }
+
+    pub(super) fn add_record(&mut self, action: Action, position: (usize, usize), outcome: Option<InterfaceOutcome>) {
+        if self.length == 0 {
+            return;
+        }
+        if self.records.len() == self.length {
+            self.records.pop_front();
+        }
+        self.records.push_back(HistoryRecord { action, position, outcome });
+    }
+
+    /// The most recent record first
+    pub(super) fn recent_records(&self) -> impl Iterator<Item = &HistoryRecord> {
+        self.records.iter().rev()
+    }
+}
@@ -0,0 +1,133 @@
+use std::mem;
+
+use robotics_lib::{event::events::Event as RobotEvent, interface::Direction, world::tile::Content};
+use rstykrab_cache::Action;
+
+use super::visualizer_event_listener::InterfaceOutcome;
+
+/// Backpack change waiting to be paired with the update of the tile next to the robot
+///
+enum BackpackChange {
+    Added,                  // content was destroyed
+    Removed(Content, usize) // content was put
+}
+
+/// Infers history cache actions from the events robot receives, so that interfaces called directly instead of via `VisualizableInterfaces`
+/// (e.g. inside `CollectTool`) appear in the action history as well. `VisualizableInterfaces` send the invocation after the interface
+/// returns, so the action inferred from the events of the call is replaced by the invocation itself. Invocations published from robot's
+/// `handle_event` arrive while the call that raised the event is in flight, they neither explain nor flush the inferred actions.
+/// Positions are (row, col) the same way as in the interface invocations.
+///
+#[derive(Default)]
+pub(super) struct ActionInference {
+    robot_position: Option<(usize, usize)>,
+    backpack_change: Option<BackpackChange>,
+    tile_update: Option<(usize, usize)>,
+    pending_actions: Vec<(Action, (usize, usize))>  // inferred actions not added to the history yet
+}
+
+impl ActionInference {
+    pub(super) fn set_robot_position(&mut self, robot_position: (usize, usize)) {
+        self.robot_position = Some(robot_position);
+    }
+
+    pub(super) fn process_event(&mut self, event: &RobotEvent) {
+        match event {
+            RobotEvent::Moved(_, position) => {
+                if let Some(action) = self.robot_position.and_then(|previous_position| infer_move(previous_position, *position)) {
+                    self.pending_actions.push((action, *position));
+                }
+                self.robot_position = Some(*position);
+                self.backpack_change = None;
+                self.tile_update = None;
+            }
+            RobotEvent::AddedToBackpack(_, _) => {
+                self.backpack_change = Some(BackpackChange::Added);
+                self.pair_backpack_change_with_tile_update();
+            }
+            RobotEvent::RemovedFromBackpack(content, amount) => {
+                self.backpack_change = Some(BackpackChange::Removed(content.clone(), *amount));
+                self.pair_backpack_change_with_tile_update();
+            }
+            RobotEvent::TileContentUpdated(_, position) => {
+                self.tile_update = Some(*position);
+                self.pair_backpack_change_with_tile_update();
+            }
+            _ => {}
+        }
+    }
+
+    /// Drops the action inferred from the events of the successful interface call the invocation was sent for. Inferred action has to have
+    /// the same effect, e.g. teleport to a neighbouring tile is inferred as `Go` and is explained by the `Teleport` invocation.
+    pub(super) fn explain(&mut self, invoked_action: &Action, robot_position: (usize, usize), outcome: &InterfaceOutcome) {
+        let explained = self.pending_actions.iter()
+            .rposition(|(action, position)| *position == robot_position && has_same_effect(action, invoked_action, robot_position, outcome));
+        if let Some(index) = explained {
+            self.pending_actions.remove(index);
+        }
+    }
+
+    /// Inferred actions in the order they happened, called before the next invocation is added to the history and at the end of the tick
+    pub(super) fn take_pending_actions(&mut self) -> Vec<(Action, (usize, usize))> {
+        self.backpack_change = None;
+        self.tile_update = None;
+        mem::take(&mut self.pending_actions)
+    }
+
+    fn pair_backpack_change_with_tile_update(&mut self) {
+        let (robot_position, tile_position) = match (self.robot_position, self.tile_update) {
+            (Some(robot_position), Some(tile_position)) if self.backpack_change.is_some() => (robot_position, tile_position),
+            _ => return,
+        };
+        self.tile_update = None;
+        // content of a tile far from the robot can change without robot's action
+        if let Some(direction) = direction_between(robot_position, tile_position) {
+            let action = match self.backpack_change.take() {
+                Some(BackpackChange::Removed(content, amount)) => Action::Put(content, amount, direction),
+                _ => Action::Destroy(direction),
+            };
+            self.pending_actions.push((action, robot_position));
+        }
+    }
+}
+
+/// Inferred actions are positioned where the robot ended up, the same way as the invocations
+fn has_same_effect(inferred_action: &Action, invoked_action: &Action, robot_position: (usize, usize), outcome: &InterfaceOutcome) -> bool {
+    match (inferred_action, invoked_action) {
+        (Action::Go(inferred_direction), Action::Go(direction)) => same_direction(inferred_direction, direction),
+        (Action::Go(_) | Action::Teleport(_), Action::Teleport(coordinates)) => *coordinates == robot_position,
+        (Action::Destroy(inferred_direction), Action::Destroy(direction)) => same_direction(inferred_direction, direction),
+        (Action::Put(inferred_content, inferred_quantity, inferred_direction), Action::Put(content, _, direction)) =>
+            inferred_content.index() == content.index() && outcome.returned_amount() == Some(*inferred_quantity) && same_direction(inferred_direction, direction),
+        _ => false,
+    }
+}
+
+fn same_direction(direction: &Direction, other_direction: &Direction) -> bool {
+    mem::discriminant(direction) == mem::discriminant(other_direction)
+}
+
+fn infer_move(previous_position: (usize, usize), position: (usize, usize)) -> Option<Action> {
+    if previous_position == position {
+        return None;
+    }
+    match direction_between(previous_position, position) {
+        Some(direction) => Some(Action::Go(direction)),
+        None => Some(Action::Teleport(position)),
+    }
+}
+
+/// Direction from `from` to the neighbouring tile `to`, None when the tiles are not neighbours
+fn direction_between((from_row, from_col): (usize, usize), (to_row, to_col): (usize, usize)) -> Option<Direction> {
+    if from_col == to_col && to_row + 1 == from_row {
+        Some(Direction::Up)
+    } else if from_col == to_col && from_row + 1 == to_row {
+        Some(Direction::Down)
+    } else if from_row == to_row && to_col + 1 == from_col {
+        Some(Direction::Left)
+    } else if from_row == to_row && from_col + 1 == to_col {
+        Some(Direction::Right)
+    } else {
+        None
+    }
+}
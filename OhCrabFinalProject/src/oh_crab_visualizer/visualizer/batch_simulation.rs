@@ -0,0 +1,205 @@
+use std::{collections::{BTreeMap, HashSet}, fs::{self, File}, io::{BufWriter, Write}, panic::{self, AssertUnwindSafe}, path::Path, sync::mpsc::{self, Receiver}};
+
+use robotics_lib::{runner::Runner, event::events::Event as RobotEvent};
+use serde::Serialize;
+
+use crate::{println_d, world_gen_utils};
+
+use super::{visualizer::OhCrabVisualizerError, visualizable_robot::{RobotCreator, VisualizableRobot, InitStateChannelItem}, visualizer_event_listener::{VisualizerEventListener, ChannelItem}};
+
+const CSV_HEADER: &str = "world_size,seed,ticks_survived,final_score,energy_consumed,energy_recharged,tiles_discovered,backpack,error";
+
+/// Metrics of a single headless run of a robot in one world, also aggregated by `MetricsSink` where world size and seed are left 0
+///
+#[derive(Serialize, Default, Debug, Clone)]
+pub struct RunMetrics {
+    pub world_size: usize,
+    pub seed: u64,
+    pub ticks_survived: usize,
+    pub final_score: f32,
+    pub energy_consumed: usize,
+    pub energy_recharged: usize,
+    pub tiles_discovered: usize,
+    pub backpack: BTreeMap<String, usize>,
+    pub error: Option<String>,      // robotic lib error or robot panic that ended the run early
+    #[serde(skip)]
+    known_tiles: HashSet<(usize, usize)>    // tiles of robot map updates, robot map itself is not kept
+}
+
+impl RunMetrics {
+    fn new(world_size: usize, seed: u64) -> RunMetrics {
+        RunMetrics {
+            world_size,
+            seed,
+            ticks_survived: 0,
+            final_score: 0.0,
+            energy_consumed: 0,
+            energy_recharged: 0,
+            tiles_discovered: 0,
+            backpack: BTreeMap::new(),
+            error: None,
+            known_tiles: HashSet::new()
+        }
+    }
+
+    pub(super) fn process_channel_item(&mut self, channel_item: &ChannelItem) {
+        match channel_item {
+            ChannelItem::EventChannelItem(event) => {
+                match event {
+                    RobotEvent::EnergyConsumed(amount) => self.energy_consumed += amount,
+                    RobotEvent::EnergyRecharged(amount) => self.energy_recharged += amount,
+                    RobotEvent::AddedToBackpack(content, amount) => {
+                        *self.backpack.entry(content.to_string()).or_insert(0) += amount;
+                    }
+                    RobotEvent::RemovedFromBackpack(content, amount) => {
+                        let content_name = content.to_string();
+                        if let Some(current_amount) = self.backpack.get_mut(&content_name) {
+                            if *current_amount > *amount {
+                                *current_amount -= amount;
+                            } else {
+                                self.backpack.remove(&content_name);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ChannelItem::RobotMapChannelItem(robot_map_update) => {
+                self.known_tiles.extend(robot_map_update.changed_tiles.iter().map(|(row, col, _)| (*row, *col)));
+                self.tiles_discovered = self.known_tiles.len();
+            }
+            ChannelItem::ScoreChannelItem(score) => self.final_score = *score,
+            ChannelItem::InterfaceChannelItem(_) | ChannelItem::PlanChannelItem(_) | ChannelItem::AnnotationChannelItem(_) | ChannelItem::TickEndedChannelItem => {}
+        }
+    }
+
+    fn to_csv_row(&self) -> String {
+        let backpack = self.backpack.iter().map(|(content, amount)| format!("{}:{}", content, amount)).collect::<Vec<String>>().join(";");
+        let error = self.error.as_ref().map(|error| csv_escape(error)).unwrap_or_default();
+        format!("{},{},{},{},{},{},{},{},{}",
+            self.world_size, self.seed, self.ticks_survived, self.final_score, self.energy_consumed, self.energy_recharged, self.tiles_discovered, csv_escape(&backpack), error)
+    }
+}
+
+/// Results of all runs of a batch simulation
+///
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchReport {
+    pub ticks: usize,
+    pub runs: Vec<RunMetrics>
+}
+
+impl BatchReport {
+    pub fn write_json(&self, report_path: &str) -> Result<(), OhCrabVisualizerError> {
+        let json = serde_json::to_string_pretty(self).map_err(|err| OhCrabVisualizerError::ReportError(format!("Serializing report failed: {}", err)))?;
+        let mut writer = create_report_file(report_path)?;
+        writeln!(writer, "{}", json)
+            .and_then(|_| writer.flush())
+            .map_err(|err| report_error(report_path, err))
+    }
+
+    pub fn write_csv(&self, report_path: &str) -> Result<(), OhCrabVisualizerError> {
+        let mut writer = create_report_file(report_path)?;
+        writeln!(writer, "{}", CSV_HEADER).map_err(|err| report_error(report_path, err))?;
+        for run in self.runs.iter() {
+            writeln!(writer, "{}", run.to_csv_row()).map_err(|err| report_error(report_path, err))?;
+        }
+        writer.flush().map_err(|err| report_error(report_path, err))
+    }
+}
+
+/// Runs robot created by `robot_creator` headlessly in every given world and collects metrics from the data robot sends to visualizer.
+/// Worlds are given as (size, seed) pairs and are loaded or generated by `world_gen_utils::load_or_generate_world`.
+///
+pub struct BatchSimulation {
+    robot_creator: Box<dyn RobotCreator>,
+    worlds: Vec<(usize, u64)>,
+    ticks: usize
+}
+
+impl BatchSimulation {
+    pub fn new(robot_creator: impl RobotCreator + 'static, worlds: Vec<(usize, u64)>, ticks: usize) -> BatchSimulation {
+        BatchSimulation { robot_creator: Box::new(robot_creator), worlds, ticks }
+    }
+
+    pub fn run(&self) -> BatchReport {
+        let runs = self.worlds.iter().map(|(world_size, seed)| self.run_world(*world_size, *seed)).collect();
+        BatchReport { ticks: self.ticks, runs }
+    }
+
+    fn run_world(&self, world_size: usize, seed: u64) -> RunMetrics {
+        println!("Batch simulation: world size {}, seed {}", world_size, seed);
+        let mut metrics = RunMetrics::new(world_size, seed);
+        let mut world_generator = world_gen_utils::load_or_generate_world(world_size, seed);
+
+        let (robot_sender, robot_receiver) = mpsc::channel::<ChannelItem>();
+        let (map_sender, map_receiver) = mpsc::channel::<InitStateChannelItem>();
+        let robot = self.robot_creator.create_forwarding(VisualizerEventListener::new(robot_sender, None));
+        let visualizable_robot = VisualizableRobot::new(robot, map_sender);
+
+        let mut runner = match Runner::new(Box::new(visualizable_robot), &mut world_generator) {
+            Ok(runner) => runner,
+            Err(robot_err) => {
+                metrics.error = Some(format!("Runner creation failed: {:?}", robot_err));
+                return metrics;
+            }
+        };
+
+        for _ in 0..self.ticks {
+            // robots are allowed to panic, panic ends the run and is reported
+            let tick_result = panic::catch_unwind(AssertUnwindSafe(|| runner.game_tick()));
+            BatchSimulation::drain_channels(&mut metrics, &robot_receiver, &map_receiver);
+            match tick_result {
+                Ok(Ok(_)) => metrics.ticks_survived += 1,
+                Ok(Err(robot_err)) => {
+                    metrics.error = Some(format!("{:?}", robot_err));
+                    break;
+                }
+                Err(panic_payload) => {
+                    metrics.error = Some(format!("Robot panicked: {}", panic_message(&panic_payload)));
+                    break;
+                }
+            }
+        }
+        println_d!("Batch simulation: finished with {:?}", metrics);
+        metrics
+    }
+
+    fn drain_channels(metrics: &mut RunMetrics, robot_receiver: &Receiver<ChannelItem>, map_receiver: &Receiver<InitStateChannelItem>) {
+        for _ in map_receiver.try_iter() {} // initial state is not needed for metrics
+        for channel_item in robot_receiver.try_iter() {
+            metrics.process_channel_item(&channel_item);
+        }
+    }
+}
+
+fn panic_message(panic_payload: &Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn create_report_file(report_path: &str) -> Result<BufWriter<File>, OhCrabVisualizerError> {
+    if let Some(parent) = Path::new(report_path).parent() {
+        fs::create_dir_all(parent).map_err(|err| report_error(report_path, err))?;
+    }
+    let file = File::create(report_path).map_err(|err| report_error(report_path, err))?;
+    println!("Writing report to {}", report_path);
+    Ok(BufWriter::new(file))
+}
+
+fn report_error(report_path: &str, error: std::io::Error) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ReportError(format!("Report file {}: {}", report_path, error))
+}
@@ -0,0 +1,82 @@
+use std::{mem::discriminant, fmt::Debug};
+
+use robotics_lib::{event::events::Event as RobotEvent, world::tile::{Content, TileType}};
+use rstykrab_cache::Action;
+
+/// Condition that pauses the visualization when it is met. Visualization stops right after the item that met the condition
+/// is processed, the rest of the tick is processed once the user continues.
+///
+#[derive(Debug)]
+pub enum Breakpoint {
+    EnergyBelow(usize),             // robot energy drops below given level
+    AddedToBackpack(Content),       // content of given type is added to backpack, amount is ignored
+    RemovedFromBackpack(Content),   // content of given type is removed from backpack, amount is ignored
+    EnteredTileType(TileType),      // robot moves to a tile of given type
+    ActionInvoked(Action)           // given interface is invoked through `VisualizableInterfaces`, parameters are ignored
+}
+
+impl Breakpoint {
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Breakpoint::EnergyBelow(level) => format!("Energy below {}", level),
+            Breakpoint::AddedToBackpack(content) => format!("Added to backpack: {}", variant_name(content)),
+            Breakpoint::RemovedFromBackpack(content) => format!("Removed from backpack: {}", variant_name(content)),
+            Breakpoint::EnteredTileType(tile_type) => format!("Entered tile: {:?}", tile_type),
+            Breakpoint::ActionInvoked(action) => format!("Action invoked: {}", variant_name(action)),
+        }
+    }
+}
+
+pub(super) struct BreakpointEntry {
+    pub(super) breakpoint: Breakpoint,
+    pub(super) enabled: bool,
+    pub(super) hit_count: usize
+}
+
+/// Breakpoints set by the user, checked for every item visualizer receives from the robot
+///
+#[derive(Default)]
+pub(super) struct Breakpoints {
+    pub(super) entries: Vec<BreakpointEntry>,
+    pub(super) last_hit: Option<String>,
+    pub(super) energy_level_input: usize    // energy level of the breakpoint added from the gui
+}
+
+impl Breakpoints {
+    pub(super) fn add(&mut self, breakpoint: Breakpoint) {
+        self.entries.push(BreakpointEntry { breakpoint, enabled: true, hit_count: 0 });
+    }
+
+    /// Returns description of the first enabled breakpoint hit by the event
+    pub(super) fn check_event(&mut self, event: &RobotEvent, energy_before: usize, energy_after: usize) -> Option<String> {
+        self.check(|breakpoint| match (breakpoint, event) {
+            (Breakpoint::EnergyBelow(level), RobotEvent::EnergyConsumed(_)) => energy_before >= *level && energy_after < *level,
+            (Breakpoint::AddedToBackpack(content), RobotEvent::AddedToBackpack(added, _)) => content.index() == added.index(),
+            (Breakpoint::RemovedFromBackpack(content), RobotEvent::RemovedFromBackpack(removed, _)) => content.index() == removed.index(),
+            (Breakpoint::EnteredTileType(tile_type), RobotEvent::Moved(tile, _)) => *tile_type == tile.tile_type,
+            _ => false
+        })
+    }
+
+    pub(super) fn check_action(&mut self, action: &Action) -> Option<String> {
+        self.check(|breakpoint| match breakpoint {
+            Breakpoint::ActionInvoked(breakpoint_action) => discriminant(breakpoint_action) == discriminant(action),
+            _ => false
+        })
+    }
+
+    fn check(&mut self, is_hit: impl Fn(&Breakpoint) -> bool) -> Option<String> {
+        let entry = self.entries.iter_mut().find(|entry| entry.enabled && is_hit(&entry.breakpoint))?;
+        entry.hit_count += 1;
+        Some(entry.breakpoint.describe())
+    }
+}
+
+/// Name of the enum variant without its parameters, e.g. `Rock` for `Rock(3)`
+fn variant_name(value: &impl Debug) -> String {
+    let debug_string = format!("{:?}", value);
+    match debug_string.find('(') {
+        Some(index) => debug_string[..index].to_owned(),
+        None => debug_string,
+    }
+}
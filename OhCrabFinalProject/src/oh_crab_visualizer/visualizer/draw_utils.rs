@@ -1,12 +1,21 @@
-use std::{collections::HashMap, hash::Hash, fmt::Debug};
+use std::{collections::{HashMap, VecDeque}, hash::Hash, fmt::Debug, mem};
 
 use ggez::{graphics::{Canvas, Color, self, TextFragment, Image}, Context, glam, mint::{Point2, Vector2}};
-use robotics_lib::world::tile::{Tile, TileType, Content};
+use robotics_lib::{world::{tile::{Tile, TileType, Content}, environmental_conditions::{EnvironmentalConditions, WeatherType}}, utils::calculate_cost_go_with_environment};
 use strum_macros::Display;
 
 use crate::println_d;
 
-use super::{Coord, visualizer::{OhCrabVisualizerError, self, CONTENT_TILE_SIZE_LIMIT, VisualizationState, ContentDisplayOptions}};
+use super::{Coord, visualizer_event_listener::RobotPlan, settings::VisualizerSettings, visualizer::{OhCrabVisualizerError, CONTENT_TILE_SIZE_LIMIT, VisualizationState, ContentDisplayOptions, MapDisplayOptions, TileShadingOptions, WorldTime}};
+
+const UNDISCOVERED_TILE_COLOR: Color = Color { r: 0.08, g: 0.08, b: 0.08, a: 1.0 };
+const UNDISCOVERED_OVERLAY_COLOR: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.65 };
+const OUTDATED_OVERLAY_COLOR: Color = Color { r: 1.0, g: 0.0, b: 0.0, a: 0.35 };
+const INSPECTED_TILE_COLOR: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+const PLANNED_PATH_COLOR: Color = Color { r: 1.0, g: 0.84, b: 0.0, a: 0.9 };
+const NOT_WALKABLE_COLOR: Color = Color { r: 0.1, g: 0.1, b: 0.1, a: 0.85 };
+const NIGHT_TINT_COLOR: Color = Color { r: 0.0, g: 0.0, b: 0.2, a: 0.45 };
+const TWILIGHT_TINT_COLOR: Color = Color { r: 1.0, g: 0.45, b: 0.1, a: 0.15 };
 
 #[derive(Default)]
 pub(super) struct GridCanvasProperties {
@@ -17,6 +26,7 @@ pub(super) struct GridCanvasProperties {
     pub(super) grid_canvas_origin_y: f32,
     pub(super) world_dimension: usize,
     pub(super) tile_size_min: f32,
+    pub(super) grid_frame_width: f32,
 }
 
 impl GridCanvasProperties {
@@ -28,16 +38,18 @@ impl GridCanvasProperties {
         (self.grid_canvas_width / self.tile_size).floor() as usize
     }
 
-    pub(super) fn build(canvas_total_size: f32, world_dimension: usize) -> GridCanvasProperties {
+    pub(super) fn build(canvas_total_size: f32, world_dimension: usize, settings: &VisualizerSettings) -> GridCanvasProperties {
         let grid_canvas_size = canvas_total_size - 80.0;
+        let (grid_canvas_origin_x, grid_canvas_origin_y) = settings.grid_canvas_origin();
         GridCanvasProperties {
-            tile_size: visualizer::DEFAULT_TILE_SIZE,
+            tile_size: settings.tiles.default_tile_size,
             grid_canvas_height: grid_canvas_size,
             grid_canvas_width: grid_canvas_size,
-            grid_canvas_origin_x: visualizer::GRID_CANVAS_ORIGIN_X,
-            grid_canvas_origin_y: visualizer::GRID_CANVAS_ORIGIN_Y,
+            grid_canvas_origin_x,
+            grid_canvas_origin_y,
             world_dimension,
-            tile_size_min: grid_canvas_size / (world_dimension as f32)
+            tile_size_min: grid_canvas_size / (world_dimension as f32),
+            grid_frame_width: settings.layout.grid_frame_width
         }
     } 
 }
@@ -87,12 +99,25 @@ impl GgezImages {
     }
 }
 
+/// Robot drawn in the tile grid, robot image is used only for the selected one
+///
+pub(super) struct RobotMarker<'a> {
+    pub(super) position: Coord,
+    pub(super) color: Color,
+    pub(super) is_selected: bool,
+    pub(super) plan: &'a RobotPlan,
+    pub(super) travelled_path: &'a VecDeque<Coord>
+}
+
 pub(super) fn draw_grid(
         ctx: &mut ggez::Context,
         canvas: &mut Canvas,
         visualization_state: &VisualizationState,
         world_map: &Vec<Vec<Tile>>,
-        robot_position: &Option<Coord>,
+        robot_map: &Option<Vec<Vec<Option<Tile>>>>,
+        heat_values: &Option<Vec<Vec<f32>>>,
+        world_time: &WorldTime,
+        robots: &[RobotMarker<'_>],
         images: &GgezImages
     ) 
     -> Result<(), OhCrabVisualizerError> {
@@ -111,29 +136,338 @@ pub(super) fn draw_grid(
     for y in tile_offset_y..last_row {
         for x in tile_offset_x..last_column {
             let tile: &Tile = &world_map[y][x]; 
-            draw_tile(tile, ctx, canvas, (x-tile_offset_x) as f32, (y-tile_offset_y) as f32,  tile_size, canvas_origin_x, canvas_origin_y, images, &visualization_state.content_display_option)?;
+            let canvas_x = (x-tile_offset_x) as f32;
+            let canvas_y = (y-tile_offset_y) as f32;
+            let known_tile = get_known_tile(robot_map, x, y);
+            match visualization_state.map_display_option {
+                MapDisplayOptions::TrueWorld => {
+                    draw_tile(tile, ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, images, &visualization_state.content_display_option)?;
+                }
+                MapDisplayOptions::RobotKnowledge => {
+                    match known_tile {
+                        Some(known_tile) => draw_tile(known_tile, ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, images, &visualization_state.content_display_option)?,
+                        None => draw_tile_rect(ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, UNDISCOVERED_TILE_COLOR)?,
+                    }
+                }
+                MapDisplayOptions::Diff => {
+                    draw_tile(tile, ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, images, &visualization_state.content_display_option)?;
+                    match known_tile {
+                        Some(known_tile) => {
+                            if known_tile.tile_type != tile.tile_type || known_tile.content != tile.content { // robot's knowledge is outdated
+                                draw_tile_rect(ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, OUTDATED_OVERLAY_COLOR)?;
+                            }
+                        }
+                        None => draw_tile_rect(ctx, canvas, canvas_x, canvas_y, tile_size, canvas_origin_x, canvas_origin_y, UNDISCOVERED_OVERLAY_COLOR)?,
+                    }
+                }
+            }
         }
     }
 
-    // robot
-    if let Some(robot_position) = robot_position {
-        if visualization_state.robot_should_be_displaied(robot_position) {
-            let robot_position_on_canvas = Coord {x: robot_position.x - tile_offset_x, y: robot_position.y - tile_offset_y };
-            draw_robot(&robot_position_on_canvas, ctx, canvas, tile_size, canvas_origin_x, canvas_origin_y, images)?;
+    // shading follows the map that is displayed
+    if visualization_state.tile_shading_option != TileShadingOptions::No {
+        let (min_elevation, max_elevation) = get_elevation_range(world_map);
+        let base_walk_costs = get_base_walk_costs(&world_time.env_conditions);
+        let mut shaded_tiles = Vec::new();  // (x, y, tile, walk cost)
+        for y in tile_offset_y..last_row {
+            for x in tile_offset_x..last_column {
+                if let Some(shaded_tile) = get_shaded_tile(&visualization_state.map_display_option, world_map, robot_map, x, y) {
+                    let walk_cost = get_walk_cost(shaded_tile, x, y, &base_walk_costs, |x, y| get_shaded_tile(&visualization_state.map_display_option, world_map, robot_map, x, y));
+                    shaded_tiles.push((x, y, shaded_tile, walk_cost));
+                }
+            }
+        }
+        // walk costs are colored relative to the displayed tiles
+        let min_walk_cost = shaded_tiles.iter().filter_map(|(_, _, _, walk_cost)| *walk_cost).min().unwrap_or(0);
+        let max_walk_cost = shaded_tiles.iter().filter_map(|(_, _, _, walk_cost)| *walk_cost).max().unwrap_or(0);
+        for (x, y, shaded_tile, walk_cost) in shaded_tiles {
+            let color = match visualization_state.tile_shading_option {
+                TileShadingOptions::Elevation => get_elevation_shade_color(shaded_tile.elevation, min_elevation, max_elevation),
+                _ => get_walk_cost_color(walk_cost, min_walk_cost, max_walk_cost),
+            };
+            draw_tile_rect(ctx, canvas, (x-tile_offset_x) as f32, (y-tile_offset_y) as f32, tile_size, canvas_origin_x, canvas_origin_y, color)?;
+        }
+    }
+
+    // heatmap
+    if let Some(heat_values) = heat_values {
+        for y in tile_offset_y..last_row {
+            for x in tile_offset_x..last_column {
+                let heat = heat_values[y][x];
+                if heat > 0.0 {
+                    draw_tile_rect(ctx, canvas, (x-tile_offset_x) as f32, (y-tile_offset_y) as f32, tile_size, canvas_origin_x, canvas_origin_y, get_heat_color(heat))?;
+                }
+            }
+        }
+    }
+
+    // day/night and weather
+    if visualization_state.show_time_tint {
+        let grid_rect = graphics::Rect::new(canvas_origin_x, canvas_origin_y, (last_column - tile_offset_x) as f32 * tile_size, (last_row - tile_offset_y) as f32 * tile_size);
+        for tint in [get_daylight_tint(world_time.hours), get_weather_tint(&world_time.weather)].into_iter().flatten() {
+            match graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), grid_rect, tint) {
+                Ok(rect) => canvas.draw(&rect, graphics::DrawParam::default()),
+                Err(error) => { return Err(OhCrabVisualizerError::GraphicsLibraryError(error)); }
+            }
+        }
+    }
+
+    // inspected tile
+    if let Some(inspected_tile) = visualization_state.inspected_tile() {
+        if let Some(center) = tile_center_on_canvas(visualization_state, inspected_tile) {
+            let half_size = tile_size * 0.5;
+            let res = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(2.0), graphics::Rect::new(center.x - half_size, center.y - half_size, tile_size, tile_size), INSPECTED_TILE_COLOR);
+            match res {
+                Ok(rect) => canvas.draw(&rect, graphics::DrawParam::default()),
+                Err(error) => { return Err(OhCrabVisualizerError::GraphicsLibraryError(error)); }
+            }
+        }
+    }
+
+    // paths
+    if visualization_state.show_robot_paths {
+        for robot in robots {
+            draw_travelled_path(ctx, canvas, visualization_state, robot.travelled_path, robot.color)?;
+            draw_plan(ctx, canvas, visualization_state, robot.plan)?;
+        }
+    }
+
+    // robots
+    for robot in robots {
+        if visualization_state.robot_should_be_displaied(&robot.position) {
+            let robot_position_on_canvas = Coord {x: robot.position.x - tile_offset_x, y: robot.position.y - tile_offset_y };
+            draw_robot(&robot_position_on_canvas, robot.color, robot.is_selected, ctx, canvas, tile_size, canvas_origin_x, canvas_origin_y, images)?;
+        }
+    }
+    Ok(())
+}
+
+/// Center of the given world tile on the canvas, None when the tile is not displayed
+fn tile_center_on_canvas(visualization_state: &VisualizationState, coord: &Coord) -> Option<glam::Vec2> {
+    if !visualization_state.robot_should_be_displaied(coord) {
+        return None;
+    }
+    let tile_size = visualization_state.grid_canvas_properties.tile_size;
+    let x = ((coord.x - visualization_state.first_column_to_display()) as f32 + 0.5) * tile_size + visualization_state.grid_canvas_properties.grid_canvas_origin_x;
+    let y = ((coord.y - visualization_state.first_row_to_display()) as f32 + 0.5) * tile_size + visualization_state.grid_canvas_properties.grid_canvas_origin_y;
+    Some(glam::Vec2::new(x, y))
+}
+
+/// Breadcrumb trail of positions robot actually visited, older positions are more transparent
+fn draw_travelled_path(ctx: &mut Context, canvas: &mut Canvas, visualization_state: &VisualizationState, travelled_path: &VecDeque<Coord>, color: Color) -> Result<(), OhCrabVisualizerError> {
+    let tile_size = visualization_state.grid_canvas_properties.tile_size;
+    let mut mesh_builder = graphics::MeshBuilder::new();
+    let mut is_empty = true;
+    for (index, coord) in travelled_path.iter().enumerate() {
+        if let Some(center) = tile_center_on_canvas(visualization_state, coord) {
+            let alpha = 0.2 + 0.6 * (index + 1) as f32 / travelled_path.len() as f32;
+            let crumb_color = Color::new(color.r, color.g, color.b, alpha);
+            mesh_builder.circle(graphics::DrawMode::fill(), center, tile_size * 0.08, 0.4, crumb_color)
+                .map_err(|error| OhCrabVisualizerError::GraphicsLibraryError(error))?;
+            is_empty = false;
+        }
+    }
+    if !is_empty {
+        let mesh = graphics::Mesh::from_data(ctx, mesh_builder.build());
+        canvas.draw(&mesh, graphics::DrawParam::default());
+    }
+    Ok(())
+}
+
+/// Planned path is drawn as a chain of arrows, target tile is outlined
+fn draw_plan(ctx: &mut Context, canvas: &mut Canvas, visualization_state: &VisualizationState, plan: &RobotPlan) -> Result<(), OhCrabVisualizerError> {
+    if plan.is_empty() {
+        return Ok(());
+    }
+    let tile_size = visualization_state.grid_canvas_properties.tile_size;
+    let mut mesh_builder = graphics::MeshBuilder::new();
+    let mut is_empty = true;
+    for step in plan.path.windows(2) {
+        let from = tile_center_on_canvas(visualization_state, &step[0]);
+        let to = tile_center_on_canvas(visualization_state, &step[1]);
+        if let (Some(from), Some(to)) = (from, to) {
+            if from == to {
+                continue;
+            }
+            let direction = (to - from).normalize();
+            let normal = glam::Vec2::new(-direction.y, direction.x);
+            let arrow_tip = to - direction * tile_size * 0.2;
+            let arrow_base = arrow_tip - direction * tile_size * 0.2;
+            mesh_builder.line(&[from, arrow_base], tile_size * 0.06, PLANNED_PATH_COLOR)
+                .and_then(|builder| builder.polygon(graphics::DrawMode::fill(), &[arrow_tip, arrow_base + normal * tile_size * 0.12, arrow_base - normal * tile_size * 0.12], PLANNED_PATH_COLOR))
+                .map_err(|error| OhCrabVisualizerError::GraphicsLibraryError(error))?;
+            is_empty = false;
         }
     }
+    if let Some(target) = &plan.target {
+        if let Some(center) = tile_center_on_canvas(visualization_state, target) {
+            let half_size = tile_size * 0.45;
+            let target_rect = graphics::Rect::new(center.x - half_size, center.y - half_size, half_size * 2.0, half_size * 2.0);
+            mesh_builder.rectangle(graphics::DrawMode::stroke(tile_size * 0.06), target_rect, PLANNED_PATH_COLOR)
+                .map_err(|error| OhCrabVisualizerError::GraphicsLibraryError(error))?;
+            is_empty = false;
+        }
+    }
+    if !is_empty {
+        let mesh = graphics::Mesh::from_data(ctx, mesh_builder.build());
+        canvas.draw(&mesh, graphics::DrawParam::default());
+    }
     Ok(())
 }
 
+fn get_known_tile(robot_map: &Option<Vec<Vec<Option<Tile>>>>, x: usize, y: usize) -> Option<&Tile> {
+    match robot_map {
+        Some(robot_map) => robot_map.get(y).and_then(|row| row.get(x)).and_then(|tile| tile.as_ref()),
+        None => None,
+    }
+}
+
+fn get_elevation_range(world_map: &Vec<Vec<Tile>>) -> (usize, usize) {
+    let elevations = world_map.iter().flat_map(|row| row.iter().map(|tile| tile.elevation));
+    let min_elevation = elevations.clone().min().unwrap_or(0);
+    let max_elevation = elevations.max().unwrap_or(0);
+    (min_elevation, max_elevation)
+}
+
+/// The lowest tiles are darkened the most, the highest ones are not darkened at all
+fn get_elevation_shade_color(elevation: usize, min_elevation: usize, max_elevation: usize) -> Color {
+    let relative_elevation = if max_elevation > min_elevation { (elevation - min_elevation) as f32 / (max_elevation - min_elevation) as f32 } else { 1.0 };
+    Color::new(0.0, 0.0, 0.0, 0.75 * (1.0 - relative_elevation))
+}
+
+fn get_shaded_tile<'a>(map_display_option: &MapDisplayOptions, world_map: &'a Vec<Vec<Tile>>, robot_map: &'a Option<Vec<Vec<Option<Tile>>>>, x: usize, y: usize) -> Option<&'a Tile> {
+    match map_display_option {
+        MapDisplayOptions::RobotKnowledge => get_known_tile(robot_map, x, y),
+        _ => world_map.get(y).and_then(|row| row.get(x))
+    }
+}
+
+/// Cost of every walkable tile type under the current weather, computed the same way as robotic lib does it when the robot moves
+fn get_base_walk_costs(env_conditions: &Option<EnvironmentalConditions>) -> Vec<(TileType, usize)> {
+    all_tile_types().into_iter()
+        .filter(|tile_type| tile_type.properties().walk())
+        .map(|tile_type| {
+            let base_cost = tile_type.properties().cost();
+            let walk_cost = match env_conditions {
+                Some(env_conditions) => calculate_cost_go_with_environment(base_cost, env_conditions.clone(), tile_type.clone()),
+                None => base_cost,
+            };
+            (tile_type, walk_cost)
+        })
+        .collect()
+}
+
+/// Cost of walking onto the tile from its lowest known neighbour: tile type cost under the current weather plus the squared elevation rise,
+/// the same way as robotic lib charges the move. None when the tile is not walkable.
+fn get_walk_cost<'a>(tile: &Tile, x: usize, y: usize, base_walk_costs: &[(TileType, usize)], get_tile: impl Fn(usize, usize) -> Option<&'a Tile>) -> Option<usize> {
+    let base_cost = base_walk_costs.iter()
+        .find(|(tile_type, _)| mem::discriminant(tile_type) == mem::discriminant(&tile.tile_type))
+        .map(|(_, walk_cost)| *walk_cost)?;
+    let neighbours = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+    let elevation_cost = neighbours.iter()
+        .filter_map(|(x, y)| get_tile(*x, *y))
+        .map(|neighbour| if tile.elevation > neighbour.elevation { (tile.elevation - neighbour.elevation).pow(2) } else { 0 })
+        .max()
+        .unwrap_or(0);
+    Some(base_cost + elevation_cost)
+}
+
+/// Goes from green for the cheapest displayed tile to red for the most expensive one
+fn get_walk_cost_color(walk_cost: Option<usize>, min_walk_cost: usize, max_walk_cost: usize) -> Color {
+    match walk_cost {
+        Some(walk_cost) => {
+            let relative_cost = if max_walk_cost > min_walk_cost { (walk_cost - min_walk_cost) as f32 / (max_walk_cost - min_walk_cost) as f32 } else { 0.0 };
+            Color::new(relative_cost, 1.0 - relative_cost, 0.0, 0.6)
+        }
+        None => NOT_WALKABLE_COLOR,
+    }
+}
+
+fn all_tile_types() -> [TileType; 11] {
+    [TileType::DeepWater, TileType::ShallowWater, TileType::Sand, TileType::Grass, TileType::Street, TileType::Hill, TileType::Mountain, TileType::Snow, TileType::Lava, TileType::Teleport(false), TileType::Wall]
+}
+
+fn get_daylight_tint(hours: u8) -> Option<Color> {
+    match hours {
+        0..=4 | 21..=23 => Some(NIGHT_TINT_COLOR),
+        5 | 19..=20 => Some(TWILIGHT_TINT_COLOR),
+        _ => None
+    }
+}
+
+fn get_weather_tint(weather: &WeatherType) -> Option<Color> {
+    match weather {
+        WeatherType::Sunny => None,
+        WeatherType::Rainy => Some(Color::new(0.3, 0.35, 0.5, 0.2)),
+        WeatherType::Foggy => Some(Color::new(0.85, 0.85, 0.85, 0.3)),
+        WeatherType::TropicalMonsoon => Some(Color::new(0.1, 0.2, 0.45, 0.3)),
+        WeatherType::TrentinoSnow => Some(Color::new(1.0, 1.0, 1.0, 0.25)),
+    }
+}
+
+/// Semi-transparent color going from yellow for the lowest heat to red for the highest one
+fn get_heat_color(heat: f32) -> Color {
+    Color::new(1.0, 0.9 * (1.0 - heat), 0.0, 0.25 + 0.45 * heat)
+}
+
+/// Color of the tile in the minimap, overlays of the grid are blended into the tile color
+pub(super) fn get_minimap_tile_color(world_map: &Vec<Vec<Tile>>, robot_map: &Option<Vec<Vec<Option<Tile>>>>, x: usize, y: usize, map_display_option: &MapDisplayOptions) -> Color {
+    let tile = &world_map[y][x];
+    let known_tile = get_known_tile(robot_map, x, y);
+    match map_display_option {
+        MapDisplayOptions::TrueWorld => get_tile_color(&tile.tile_type),
+        MapDisplayOptions::RobotKnowledge => match known_tile {
+            Some(known_tile) => get_tile_color(&known_tile.tile_type),
+            None => UNDISCOVERED_TILE_COLOR,
+        }
+        MapDisplayOptions::Diff => match known_tile {
+            Some(known_tile) if known_tile.tile_type != tile.tile_type || known_tile.content != tile.content => blend_colors(get_tile_color(&tile.tile_type), OUTDATED_OVERLAY_COLOR),
+            Some(_) => get_tile_color(&tile.tile_type),
+            None => blend_colors(get_tile_color(&tile.tile_type), UNDISCOVERED_OVERLAY_COLOR),
+        }
+    }
+}
+
+fn blend_colors(base: Color, overlay: Color) -> Color {
+    Color::new(
+        base.r * (1.0 - overlay.a) + overlay.r * overlay.a,
+        base.g * (1.0 - overlay.a) + overlay.g * overlay.a,
+        base.b * (1.0 - overlay.a) + overlay.b * overlay.a,
+        1.0
+    )
+}
+
+/// Draws rectangle of the size of a tile, used for undiscovered tiles and semi-transparent overlays
+fn draw_tile_rect(ctx: &mut Context, canvas: &mut Canvas, x: f32, y :f32, tile_size: f32, grid_canvas_origin_x: f32, grid_canvas_origin_y: f32, color: Color) -> Result<(), OhCrabVisualizerError> {
+    let res = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        graphics::Rect::new(
+            (x * tile_size) + grid_canvas_origin_x,
+            (y * tile_size) + grid_canvas_origin_y,
+            tile_size,
+            tile_size,
+        ),
+        color
+    );
+    match res {
+        Ok(rect) => {
+            canvas.draw(&rect, graphics::DrawParam::default());
+            Ok(())
+        }
+        Err(error) => Err(OhCrabVisualizerError::GraphicsLibraryError(error))
+    }
+}
+
 fn draw_grid_frame(ctx: &mut Context, canvas: &mut Canvas, canvas_props: &GridCanvasProperties) -> Result<(), OhCrabVisualizerError> {
     let res = graphics::Mesh::new_rectangle(
         ctx,
         graphics::DrawMode::fill(),
         graphics::Rect::new(
-            canvas_props.grid_canvas_origin_x - visualizer::GRID_FRAME_WIDTH,
-            canvas_props.grid_canvas_origin_y - visualizer::GRID_FRAME_WIDTH,
-            canvas_props.grid_canvas_width + (visualizer::GRID_FRAME_WIDTH * 2.0),
-            canvas_props.grid_canvas_width  + (visualizer::GRID_FRAME_WIDTH * 2.0),
+            canvas_props.grid_canvas_origin_x - canvas_props.grid_frame_width,
+            canvas_props.grid_canvas_origin_y - canvas_props.grid_frame_width,
+            canvas_props.grid_canvas_width + (canvas_props.grid_frame_width * 2.0),
+            canvas_props.grid_canvas_width  + (canvas_props.grid_frame_width * 2.0),
         ),
         Color::from_rgb(128, 128, 128)
     );
@@ -242,10 +576,10 @@ pub fn draw_content(canvas: &mut Canvas, content: &Content, x: f32, y:f32, size:
     draw_text(canvas, x, y, color, size, text)
 }
 
-fn draw_robot(robot_position: &Coord, ctx: &mut Context, canvas: &mut Canvas, tile_size: f32, grid_canvas_origin_x: f32, grid_canvas_origin_y: f32, images: &GgezImages) -> Result<(), OhCrabVisualizerError> {
+fn draw_robot(robot_position: &Coord, color: Color, is_selected: bool, ctx: &mut Context, canvas: &mut Canvas, tile_size: f32, grid_canvas_origin_x: f32, grid_canvas_origin_y: f32, images: &GgezImages) -> Result<(), OhCrabVisualizerError> {
     let x = robot_position.x;
     let y = robot_position.y;
-    if  tile_size >= CONTENT_TILE_SIZE_LIMIT + 10 as f32 && images.robot_image.is_some() {
+    if  is_selected && tile_size >= CONTENT_TILE_SIZE_LIMIT + 10 as f32 && images.robot_image.is_some() {
         let center_x = ((x as f32 + 0.3) * tile_size) + grid_canvas_origin_x;
         let center_y = ((y as f32 + 0.0) * tile_size) + grid_canvas_origin_y;
         let robot_image: &Image = images.robot_image.as_ref().unwrap();
@@ -263,13 +597,13 @@ fn draw_robot(robot_position: &Coord, ctx: &mut Context, canvas: &mut Canvas, ti
         let circle_radius = tile_size * 0.2;
         let center_x = ((x as f32 + 0.25) * tile_size) + grid_canvas_origin_x;
         let center_y = (y as f32 + 0.25) * tile_size + grid_canvas_origin_y;
-        let res_black = graphics::Mesh::new_circle(
+        let res_robot_color = graphics::Mesh::new_circle(
             ctx,
             graphics::DrawMode::fill(),
             glam::Vec2::new(center_x, center_y),
             circle_radius,
             0.4,
-            Color::BLACK,
+            color,
         );
         let res_white = graphics::Mesh::new_circle(
             ctx,
@@ -285,7 +619,7 @@ fn draw_robot(robot_position: &Coord, ctx: &mut Context, canvas: &mut Canvas, ti
             }
             Err(error) => return Err(OhCrabVisualizerError::GraphicsLibraryError(error))
         }
-        match res_black {
+        match res_robot_color {
             Ok(circle) => {
                 canvas.draw(&circle, graphics::DrawParam::default());
                 Ok(())
@@ -323,7 +657,7 @@ pub(super) fn draw_text(canvas: &mut Canvas, x: f32, y:f32, color: Color, size:f
 //     }
 // }
 
-fn get_tile_color(tile_type: &TileType) -> Color {
+pub(super) fn get_tile_color(tile_type: &TileType) -> Color {
     match tile_type {
         TileType::DeepWater => Color::from_rgb(20, 21, 123),      
         TileType::ShallowWater => Color::from_rgb(125, 245, 234),     
@@ -339,7 +673,7 @@ fn get_tile_color(tile_type: &TileType) -> Color {
     }
 }
 
-fn get_content_string(content: &Content) -> String {
+pub(super) fn get_content_string(content: &Content) -> String {
     match content {
         Content::Rock(val) => format!("Rock({})", val),
         Content::Tree(val) => format!("Tree({})", val),
@@ -473,7 +807,7 @@ fn all_enum_variants<T: std::fmt::Debug>() -> Vec<T> {
     variants
 }
 
-fn remove_content_between_parentheses(input: &str) -> String {
+pub(super) fn remove_content_between_parentheses(input: &str) -> String {
     let mut result = String::new();
     let mut in_parentheses = false;
     for c in input.chars() {
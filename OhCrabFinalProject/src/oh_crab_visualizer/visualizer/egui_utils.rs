@@ -2,17 +2,19 @@ use std::collections::HashMap;
 
 use egui::Image;
 use egui_extras::{TableBuilder, Column};
+use egui_plot::{Plot, Line, PlotPoints, VLine, Legend, LineStyle};
 use ggegui::{GuiContext, egui::{self, Layout}};
-use robotics_lib::{world::{tile::Content, environmental_conditions::WeatherType}, interface::Direction};
-use rstykrab_cache::{Record, Action};
+use robotics_lib::{world::{tile::{Content, Tile}, environmental_conditions::{WeatherType, EnvironmentalConditions}}, interface::Direction, utils::calculate_cost_go_with_environment};
+use rstykrab_cache::Action;
 
-use super::visualizer::{VisualizationState, WorldTime, MAX_ENERGY_LEVEL};
+use super::{Coord, visualizer::{VisualizationState, WorldTime, ChartDisplayOptions, HistoryFilterOptions, MapDisplayOptions}, robot_session::RobotSession, time_series::TickSample, breakpoints::{Breakpoints, Breakpoint}, visualizer_event_listener::InterfaceOutcome, action_history::ActionHistory, draw_utils::{get_content_string, get_minimap_tile_color, RobotMarker}};
 
 const COLON_KEY:u8 = 42;
 const DIRECTION_UP:u8 = 0;
 const DIRECTION_RIGHT:u8 = 1;
 const DIRECTION_DOWN:u8 = 2;
 const DIRECTION_LEFT:u8 = 3;
+const MINIMAP_MIN_SIZE: f32 = 200.0;
 
 pub(super) struct EguiImages<'a> {
     content_images: HashMap<Content, Image<'a>>,
@@ -173,7 +175,8 @@ fn get_digits(number: u8) -> (u8, u8) {
 }
 
 pub(super) fn draw_energy_bar(ctx: &egui::Context, visualizatio_state: &VisualizationState, robot_energy: usize, energy_difference: i32, egui_images: &EguiImages) {
-    let energy_percentage = robot_energy as f32 / MAX_ENERGY_LEVEL as f32;
+    let max_energy_level = visualizatio_state.settings.max_energy_level;
+    let energy_percentage = robot_energy as f32 / max_energy_level as f32;
 
     egui::Window::new("Robot energy")
         .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width + 40.0, 15.0))
@@ -181,7 +184,7 @@ pub(super) fn draw_energy_bar(ctx: &egui::Context, visualizatio_state: &Visualiz
         .show(ctx, |ui| {
             let energy_bar = egui::ProgressBar::new(energy_percentage)
             .fill(egui::Color32::from_rgb(255, 51, 0))
-            .text(format!("{} / {}", robot_energy, MAX_ENERGY_LEVEL));
+            .text(format!("{} / {}", robot_energy, max_energy_level));
             //.show_percentage();
             ui.add(energy_bar);
 
@@ -194,6 +197,306 @@ pub(super) fn draw_energy_bar(ctx: &egui::Context, visualizatio_state: &Visualiz
         });
 }
 
+/// Lists all visualized robots, panels and camera follow the selected one
+pub(super) fn draw_robot_selector(gui_ctx: &mut GuiContext, visualizatio_state: &VisualizationState, robots: &Vec<RobotSession>, selected_robot: &mut usize) {
+    egui::Window::new("Robots")
+        .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width + 40.0, 320.0))
+        .show(gui_ctx, |ui| {
+            let table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(false)
+            .cell_layout(Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .min_scrolled_height(0.0);
+
+            table
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("Robot");
+                });
+                header.col(|ui| {
+                    ui.strong("Energy");
+                });
+                header.col(|ui| {
+                    ui.strong("Backpack");
+                });
+            })
+            .body(|mut body|
+                for (index, robot) in robots.iter().enumerate() {
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            let (r, g, b) = robot.color;
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), "●");
+                            ui.radio_value(selected_robot, index, robot.name.as_str());
+                        });
+                        row.col(|ui| {
+                            ui.label(robot.world_state.robot_energy.to_string());
+                        });
+                        row.col(|ui| {
+                            let items: usize = robot.world_state.backpack.values().sum();
+                            ui.label(items.to_string());
+                        });
+                    });
+        });
+    });
+}
+
+/// Plots selected time series of all robots, vertical line marks the displayed tick.
+/// Returns true when user asked to export time series of the selected robot.
+pub(super) fn draw_charts(gui_ctx: &mut GuiContext, visualizatio_state: &mut VisualizationState, robots: &Vec<RobotSession>, displayed_tick: usize) -> bool {
+    let mut export_clicked = false;
+    egui::Window::new("Charts")
+        .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + 20.0, visualizatio_state.grid_canvas_properties.grid_canvas_width - 200.0))
+        .default_open(false)
+        .show(gui_ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut visualizatio_state.chart_display_option, ChartDisplayOptions::Energy, "Energy");
+                ui.radio_value(&mut visualizatio_state.chart_display_option, ChartDisplayOptions::EnergyFlow, "Energy per tick");
+                ui.radio_value(&mut visualizatio_state.chart_display_option, ChartDisplayOptions::Score, "Score");
+                ui.radio_value(&mut visualizatio_state.chart_display_option, ChartDisplayOptions::Backpack, "Backpack");
+            });
+
+            Plot::new("time_series_plot")
+                .legend(Legend::default())
+                .height(220.0)
+                .width(500.0)
+                .show(ui, |plot_ui| {
+                    for robot in robots.iter() {
+                        let color = get_chart_color(robot.color);
+                        let samples = robot.time_series.samples();
+                        match visualizatio_state.chart_display_option {
+                            ChartDisplayOptions::Energy => {
+                                plot_ui.line(get_series_line(samples, |sample| sample.energy as f64).name(format!("{} energy", robot.name)).color(color));
+                            }
+                            ChartDisplayOptions::EnergyFlow => {
+                                plot_ui.line(get_series_line(samples, |sample| sample.energy_consumed as f64).name(format!("{} consumed", robot.name)).color(color));
+                                plot_ui.line(get_series_line(samples, |sample| sample.energy_recharged as f64).name(format!("{} recharged", robot.name)).color(color).style(LineStyle::dashed_loose()));
+                            }
+                            ChartDisplayOptions::Score => {
+                                plot_ui.line(get_series_line(samples, |sample| sample.score as f64).name(format!("{} score", robot.name)).color(color));
+                            }
+                            ChartDisplayOptions::Backpack => {
+                                plot_ui.line(get_series_line(samples, |sample| sample.backpack_fill as f64).name(format!("{} backpack", robot.name)).color(color));
+                            }
+                        }
+                    }
+                    plot_ui.vline(VLine::new(displayed_tick as f64).color(egui::Color32::GRAY));
+                });
+
+            ui.horizontal(|ui| {
+                if ui.add(egui::Button::new("Export CSV")).clicked() {
+                    export_clicked = true;
+                }
+                if let Some(message) = &visualizatio_state.chart_export_message {
+                    ui.label(message);
+                }
+            });
+        });
+    export_clicked
+}
+
+fn get_series_line(samples: &Vec<TickSample>, value: impl Fn(&TickSample) -> f64) -> Line {
+    let points: Vec<[f64; 2]> = samples.iter().map(|sample| [sample.tick as f64, value(sample)]).collect();
+    Line::new(PlotPoints::from(points))
+}
+
+/// Robots are plotted in their colors, black robot would not be visible on dark background
+fn get_chart_color((r, g, b): (u8, u8, u8)) -> egui::Color32 {
+    if (r, g, b) == (0, 0, 0) { egui::Color32::LIGHT_GRAY } else { egui::Color32::from_rgb(r, g, b) }
+}
+
+/// Whole world drawn with one pixel per tile, texture is redrawn only when the displayed map could have changed
+///
+#[derive(Default)]
+pub(super) struct Minimap {
+    texture: Option<egui::TextureHandle>,
+    drawn_for: Option<(usize, usize, MapDisplayOptions)>    // selected robot, displayed tick and map option of the texture
+}
+
+impl Minimap {
+    /// `tick_in_progress` forces the redraw, tiles are discovered also in the middle of the tick
+    pub(super) fn update(&mut self, ctx: &egui::Context, drawn_for: (usize, usize, MapDisplayOptions), tick_in_progress: bool, world_map: &Vec<Vec<Tile>>, robot_map: &Option<Vec<Vec<Option<Tile>>>>) {
+        if self.texture.is_some() && self.drawn_for == Some(drawn_for) && !tick_in_progress {
+            return;
+        }
+        let world_dimension = world_map.len();
+        let mut pixels: Vec<egui::Color32> = Vec::with_capacity(world_dimension * world_dimension);
+        for y in 0..world_dimension {
+            for x in 0..world_dimension {
+                let (r, g, b, _) = get_minimap_tile_color(world_map, robot_map, x, y, &drawn_for.2).to_rgba();
+                pixels.push(egui::Color32::from_rgb(r, g, b));
+            }
+        }
+        let image = egui::ColorImage { size: [world_dimension, world_dimension], pixels };
+        match &mut self.texture {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => self.texture = Some(ctx.load_texture("minimap", image, egui::TextureOptions::NEAREST)),
+        }
+        self.drawn_for = Some(drawn_for);
+    }
+}
+
+/// Draws the minimap with robots and rectangle of the part of the world displayed in the grid.
+/// Returns the tile user clicked or dragged to.
+pub(super) fn draw_minimap(gui_ctx: &mut GuiContext, visualizatio_state: &VisualizationState, minimap: &Minimap, robots: &[RobotMarker<'_>]) -> Option<Coord> {
+    let mut target_tile = None;
+    let texture = minimap.texture.as_ref()?;
+    let world_dimension = visualizatio_state.grid_canvas_properties.world_dimension;
+    if world_dimension == 0 {
+        return None;
+    }
+    // small worlds are scaled up so that the minimap is usable
+    let pixels_per_tile = f32::max(1.0, (MINIMAP_MIN_SIZE / world_dimension as f32).floor());
+    let minimap_size = world_dimension as f32 * pixels_per_tile;
+
+    egui::Window::new("Minimap")
+        .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width + 40.0, 500.0))
+        .default_open(false)
+        .resizable(false)
+        .show(gui_ctx, |ui| {
+            let image = egui::Image::new(egui::load::SizedTexture::new(texture.id(), egui::vec2(minimap_size, minimap_size)))
+                .sense(egui::Sense::click_and_drag());
+            let response = ui.add(image);
+            let origin = response.rect.min;
+            let painter = ui.painter_at(response.rect);
+
+            for robot in robots {
+                let (r, g, b, _) = robot.color.to_rgba();
+                let center = origin + egui::vec2((robot.position.x as f32 + 0.5) * pixels_per_tile, (robot.position.y as f32 + 0.5) * pixels_per_tile);
+                let radius = f32::max(3.0, pixels_per_tile);
+                painter.circle(center, radius, egui::Color32::from_rgb(r, g, b), egui::Stroke::new(1.0, egui::Color32::WHITE));
+            }
+
+            let viewport = egui::Rect::from_min_max(
+                origin + egui::vec2(visualizatio_state.first_column_to_display() as f32, visualizatio_state.first_row_to_display() as f32) * pixels_per_tile,
+                origin + egui::vec2(visualizatio_state.get_last_column_to_display() as f32, visualizatio_state.get_last_row_to_display() as f32) * pixels_per_tile
+            );
+            painter.rect_stroke(viewport, 0.0, egui::Stroke::new(1.5, egui::Color32::WHITE));
+
+            if response.clicked() || response.dragged() {
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    let position = (pointer - origin) / pixels_per_tile;
+                    let x = usize::min(world_dimension - 1, f32::max(0.0, position.x) as usize);
+                    let y = usize::min(world_dimension - 1, f32::max(0.0, position.y) as usize);
+                    target_tile = Some(Coord::new(x, y));
+                }
+            }
+        });
+    target_tile
+}
+
+pub(super) fn draw_breakpoints(gui_ctx: &mut GuiContext, visualizatio_state: &VisualizationState, breakpoints: &mut Breakpoints, halted_at_breakpoint: bool) {
+    egui::Window::new("Breakpoints")
+        .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width + 40.0, 400.0))
+        .default_open(false)
+        .show(gui_ctx, |ui| {
+            let mut to_remove = None;
+            for (index, entry) in breakpoints.entries.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut entry.enabled, entry.breakpoint.describe());
+                    ui.label(format!("hits: {}", entry.hit_count));
+                    if ui.small_button("Remove").clicked() {
+                        to_remove = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = to_remove {
+                breakpoints.entries.remove(index);
+            }
+            if breakpoints.entries.is_empty() {
+                ui.label("No breakpoints set.");
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Energy below: ");
+                ui.add(egui::DragValue::new(&mut breakpoints.energy_level_input).clamp_range(0..=visualizatio_state.settings.max_energy_level));
+                if ui.add(egui::Button::new("Add")).clicked() {
+                    breakpoints.add(Breakpoint::EnergyBelow(breakpoints.energy_level_input));
+                }
+            });
+
+            if let Some(last_hit) = &breakpoints.last_hit {
+                ui.separator();
+                if halted_at_breakpoint {
+                    ui.strong(format!("Paused at {}", last_hit));
+                } else {
+                    ui.label(format!("Last hit: {}", last_hit));
+                }
+            }
+        });
+}
+
+/// Shows details of the inspected tile, walk cost is computed the same way robot's path search does it.
+/// Returns true when user asked to unpin the tile.
+pub(super) fn draw_tile_inspector(gui_ctx: &mut GuiContext, visualizatio_state: &VisualizationState, coord: &Coord, tile: &Tile, known_tile: Option<&Tile>, robot_tile: Option<&Tile>, env_conditions: &Option<EnvironmentalConditions>, is_pinned: bool) -> bool {
+    let mut unpin = false;
+    egui::Window::new("Tile inspector")
+        .default_pos((5.0, 420.0))
+        .show(gui_ctx, |ui| {
+            egui::Grid::new("tile_inspector_grid").num_columns(2).striped(true).show(ui, |ui| {
+                ui.strong("Coordinates");
+                ui.label(format!("x: {}, y: {} (row {}, col {})", coord.x, coord.y, coord.y, coord.x));
+                ui.end_row();
+
+                ui.strong("Tile type");
+                ui.label(format!("{:?}", tile.tile_type));
+                ui.end_row();
+
+                ui.strong("Content");
+                ui.label(get_content_string(&tile.content));
+                ui.end_row();
+
+                ui.strong("Elevation");
+                ui.label(tile.elevation.to_string());
+                ui.end_row();
+
+                ui.strong("Walkable");
+                ui.label(if tile.tile_type.properties().walk() { "yes" } else { "no" });
+                ui.end_row();
+
+                let base_cost = tile.tile_type.properties().cost();
+                ui.strong("Walk cost");
+                match env_conditions {
+                    Some(env_conditions) => {
+                        let weather_cost = calculate_cost_go_with_environment(base_cost, env_conditions.clone(), tile.tile_type.clone());
+                        ui.label(format!("{} (base {})", weather_cost, base_cost));
+                    }
+                    None => { ui.label(format!("{} (weather unknown)", base_cost)); }
+                }
+                ui.end_row();
+
+                if let Some(robot_tile) = robot_tile {
+                    let elevation_cost = if tile.elevation > robot_tile.elevation { (tile.elevation - robot_tile.elevation).pow(2) } else { 0 };
+                    ui.strong("Elevation cost");
+                    ui.label(format!("{} (from robot's tile)", elevation_cost));
+                    ui.end_row();
+                }
+
+                ui.strong("Discovered");
+                match known_tile {
+                    Some(known_tile) if known_tile.tile_type != tile.tile_type || known_tile.content != tile.content => {
+                        ui.label(format!("yes, outdated: robot knows {:?} with {}", known_tile.tile_type, get_content_string(&known_tile.content)));
+                    }
+                    Some(_) => { ui.label("yes"); }
+                    None => { ui.label("no"); }
+                }
+                ui.end_row();
+            });
+            if is_pinned {
+                if ui.add(egui::Button::new("Unpin")).clicked() {
+                    unpin = true;
+                }
+            }
+            else {
+                ui.label("Click the tile to pin it.");
+            }
+        });
+    unpin
+}
+
 pub(super) fn draw_rizler_message(ctx: &egui::Context, visualizatio_state: &VisualizationState, riz_message: &Option<String>) {
     egui::Window::new("Rizzler")
         .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width +  280.0, 500.0))
@@ -207,10 +510,17 @@ pub(super) fn draw_rizler_message(ctx: &egui::Context, visualizatio_state: &Visu
         });
 }
 
-pub(super) fn draw_history_cache(gui_ctx: &mut GuiContext, visualizatio_state: &VisualizationState, cached_actions: &Vec<&Record>, egui_images: &EguiImages) {
+/// Actions are colour-coded by the outcome of the interface call, grey actions were inferred from events
+pub(super) fn draw_history_cache(gui_ctx: &mut GuiContext, visualizatio_state: &mut VisualizationState, action_history: &ActionHistory, egui_images: &EguiImages) {
     egui::Window::new("Robot action history")
         .default_pos((visualizatio_state.grid_canvas_properties.grid_canvas_origin_x + visualizatio_state.grid_canvas_properties.grid_canvas_width + 40.0, 500.0))
         .show(gui_ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut visualizatio_state.history_filter_option, HistoryFilterOptions::All, "All");
+                ui.radio_value(&mut visualizatio_state.history_filter_option, HistoryFilterOptions::Succeeded, "Succeeded");
+                ui.radio_value(&mut visualizatio_state.history_filter_option, HistoryFilterOptions::Failed, "Failed");
+            });
+
             let table = TableBuilder::new(ui)
             .striped(true)
             .resizable(false)
@@ -218,6 +528,8 @@ pub(super) fn draw_history_cache(gui_ctx: &mut GuiContext, visualizatio_state: &
             .column(Column::auto())
             .column(Column::remainder())
             .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
             .min_scrolled_height(0.0);
 
             table
@@ -231,28 +543,73 @@ pub(super) fn draw_history_cache(gui_ctx: &mut GuiContext, visualizatio_state: &
                 header.col(|ui| {
                     ui.label("        ");
                 });
+                header.col(|ui| {
+                    ui.strong("Result");
+                });
+                header.col(|ui| {
+                    ui.strong("Energy");
+                });
             })
             .body(|mut body|
-                for action_record in cached_actions.iter() {
+                for action_record in action_history.recent_records() {
+                    let outcome = action_record.outcome.as_ref();
+                    let succeeded = outcome.map_or(true, |outcome| outcome.succeeded());
+                    let displayed = match visualizatio_state.history_filter_option {
+                        HistoryFilterOptions::All => true,
+                        HistoryFilterOptions::Succeeded => succeeded,
+                        HistoryFilterOptions::Failed => !succeeded,
+                    };
+                    if !displayed {
+                        continue;
+                    }
                     let row_height = 20.0 ;
                     body.row(row_height, |mut row| {
-                        let (action_label, image) = cache_action_to_visualization(action_record.action(), &egui_images);
+                        let (action_label, image) = cache_action_to_visualization(&action_record.action, &egui_images);
+                        let (result_label, color) = outcome_to_visualization(outcome);
                         row.col(|ui| {
                             ui.label(format!("{},{}", action_record.position.0, action_record.position.1));
                         });
                         row.col(|ui| {
-                            ui.label(action_label);
+                            ui.colored_label(color, action_label);
                         });
                         row.col(|ui| {
                             if let Some(image) = image {
                                 ui.add(image);
                             }
                         });
+                        row.col(|ui| {
+                            ui.colored_label(color, result_label);
+                        });
+                        row.col(|ui| {
+                            if let Some(outcome) = outcome {
+                                ui.label(energy_change_label(outcome.energy_cost()));
+                            }
+                        });
                     });
         });
     });
 }
 
+/// Energy cost shown as the change of robot's energy, recharge by a traced tool is positive
+fn energy_change_label(energy_cost: i64) -> String {
+    match energy_cost {
+        0 => "0".to_owned(),
+        cost if cost < 0 => format!("+{}", -cost),
+        cost => format!("-{}", cost),
+    }
+}
+
+fn outcome_to_visualization(outcome: Option<&InterfaceOutcome>) -> (String, egui::Color32) {
+    match outcome {
+        Some(outcome) => match (outcome.error(), outcome.returned_amount()) {
+            (Some(error), _) => (error.to_owned(), egui::Color32::LIGHT_RED),
+            (None, Some(amount)) => (format!("Ok: {}", amount), egui::Color32::LIGHT_GREEN),
+            (None, None) => ("Ok".to_owned(), egui::Color32::LIGHT_GREEN),
+        },
+        None => ("Inferred".to_owned(), egui::Color32::GRAY),
+    }
+}
+
 fn cache_action_to_visualization<'a>(action: &Action, images: &EguiImages<'a>) -> (String, Option<Image<'a>>) {
     match action {
         Action::Craft(content) => (format!("Craft: {}", content), images.get_image_for_content(content)),
@@ -267,7 +624,7 @@ fn cache_action_to_visualization<'a>(action: &Action, images: &EguiImages<'a>) -
         Action::RobotView() => (format!("Robot view"), None),
         Action::Teleport((x,y)) => (format!("Teleport ({},{})", x, y), None),
         Action::WhereAmI() => (format!("Where am I"), None),
-        Action::Other(_) => ("".to_owned(), None)
+        Action::Other(label) => (label.to_owned(), None)
     }        
 }
 
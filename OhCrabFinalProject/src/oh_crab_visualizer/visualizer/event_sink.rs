@@ -0,0 +1,152 @@
+use std::{cell::RefCell, fs::{self, File}, io::{BufWriter, Write}, path::Path, rc::Rc, sync::mpsc::Sender, time::{SystemTime, UNIX_EPOCH}};
+
+use oxagaudiotool::OxAgAudioTool;
+use serde::Serialize;
+
+use crate::oh_crab_visualizer::audio::get_configured_audio_tool;
+
+use super::{visualizer::OhCrabVisualizerError, visualizer_event_listener::ChannelItem, settings::VisualizerSettings, serializable::SerializableChannelItem, batch_simulation::RunMetrics};
+
+/// Creates additional sinks for the robot with given name, see `OhCrabVisualizerConfig::with_event_sink`
+pub type EventSinkFactory = Box<dyn Fn(&str) -> Box<dyn EventSink>>;
+
+/// Consumer of the data robot publishes via `VisualizerEventListener`. Listener passes every item to all its sinks,
+/// so the same robot can run under the visualizer, headlessly with a file logger or in tests with a custom sink.
+///
+pub trait EventSink {
+    fn consume(&mut self, item: &ChannelItem);
+}
+
+/// Sends items to the visualizer, the receiving end is owned by `RobotSession` or `BatchSimulation`
+///
+pub(super) struct ChannelSink {
+    sender: Sender<ChannelItem>
+}
+
+impl ChannelSink {
+    pub(super) fn new(sender: Sender<ChannelItem>) -> ChannelSink {
+        ChannelSink { sender }
+    }
+}
+
+impl EventSink for ChannelSink {
+    fn consume(&mut self, item: &ChannelItem) {
+        self.sender.send(item.clone()).expect("VisualizerDataSender: sending item failed.");
+    }
+}
+
+/// Plays sounds of robot events
+///
+pub struct AudioSink {
+    audio_tool: OxAgAudioTool
+}
+
+impl AudioSink {
+    /// Sounds and volumes are taken from `settings.assets` and `settings.sound`
+    pub fn new(settings: &VisualizerSettings) -> Result<AudioSink, OhCrabVisualizerError> {
+        let audio_tool = get_configured_audio_tool(&settings.assets, &settings.sound)
+            .map_err(|err| OhCrabVisualizerError::ConfigurationError(format!("Audio tool error: {}", err)))?;
+        Ok(AudioSink { audio_tool })
+    }
+}
+
+impl EventSink for AudioSink {
+    fn consume(&mut self, item: &ChannelItem) {
+        if let ChannelItem::EventChannelItem(event) = item {
+            if let Err(err) = self.audio_tool.play_audio_based_on_event(event) {
+                println!("Audio tool error: {}", err);
+            }
+        }
+    }
+}
+
+/// One line of the event log
+///
+#[derive(Serialize)]
+struct EventLogLine {
+    tick: usize,
+    timestamp_ms: u128,     // milliseconds since unix epoch
+    entry: EventLogEntry
+}
+
+#[derive(Serialize)]
+enum EventLogEntry {
+    Item(SerializableChannelItem),  // event, interface invocation with rizzler message, robot map update, score, plan or annotation
+    TickEnded
+}
+
+/// Structured log of the run, every item is written as one JSON line with tick number and timestamp.
+/// Ticks are counted by the `TickEndedChannelItem` published after every tick of the robot, it is written as `TickEnded` line.
+///
+pub struct JsonLinesSink {
+    writer: BufWriter<File>,
+    tick: usize
+}
+
+impl JsonLinesSink {
+    pub fn new(log_path: &str) -> Result<JsonLinesSink, OhCrabVisualizerError> {
+        if let Some(parent) = Path::new(log_path).parent() {
+            fs::create_dir_all(parent).map_err(|err| sink_error(log_path, err))?;
+        }
+        let file = File::create(log_path).map_err(|err| sink_error(log_path, err))?;
+        println!("Logging events to {}", log_path);
+        Ok(JsonLinesSink { writer: BufWriter::new(file), tick: 0 })
+    }
+
+    fn write_item(&mut self, item: &ChannelItem) -> Result<(), String> {
+        if let ChannelItem::TickEndedChannelItem = item {
+            self.write_entry(EventLogEntry::TickEnded)?;
+            self.tick += 1;
+            return Ok(());
+        }
+        self.write_entry(EventLogEntry::Item(SerializableChannelItem::from_channel_item(item)))
+    }
+
+    fn write_entry(&mut self, entry: EventLogEntry) -> Result<(), String> {
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis()).unwrap_or_default();
+        let line = serde_json::to_string(&EventLogLine { tick: self.tick, timestamp_ms, entry }).map_err(|err| err.to_string())?;
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl EventSink for JsonLinesSink {
+    fn consume(&mut self, item: &ChannelItem) {
+        if let Err(err) = self.write_item(item) {
+            println!("Event log error: {}", err);
+        }
+    }
+}
+
+/// Aggregates the same `RunMetrics` as `BatchSimulation`, ticks are counted by `TickEndedChannelItem`.
+/// The sink is cloned before it is given to the listener and the clone is used to read the metrics.
+///
+#[derive(Default, Clone)]
+pub struct MetricsSink {
+    metrics: Rc<RefCell<RunMetrics>>
+}
+
+impl MetricsSink {
+    pub fn new() -> MetricsSink {
+        MetricsSink::default()
+    }
+
+    pub fn metrics(&self) -> RunMetrics {
+        self.metrics.borrow().clone()
+    }
+}
+
+impl EventSink for MetricsSink {
+    fn consume(&mut self, item: &ChannelItem) {
+        let mut metrics = self.metrics.borrow_mut();
+        if let ChannelItem::TickEndedChannelItem = item {
+            metrics.ticks_survived += 1;
+        }
+        metrics.process_channel_item(item);
+    }
+}
+
+fn sink_error(log_path: &str, error: std::io::Error) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ConfigurationError(format!("Event log {}: {}", log_path, error))
+}
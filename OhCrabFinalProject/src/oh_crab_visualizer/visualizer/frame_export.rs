@@ -0,0 +1,223 @@
+use std::{collections::{HashMap, HashSet}, fs::{self, File}, io::BufWriter, path::Path};
+
+use ggez::graphics::Color;
+use image::{RgbaImage, Rgba, imageops::{self, FilterType}, codecs::gif::{GifEncoder, Repeat}, Frame, Delay};
+use robotics_lib::world::tile::{Tile, Content};
+
+use crate::println_d;
+
+use super::{visualizer::OhCrabVisualizerError, draw_utils::{RobotMarker, get_tile_color, remove_content_between_parentheses}};
+
+const CONTENT_IMAGE_SIZE_LIMIT: u32 = 12; // smaller tiles are drawn without content
+const GIF_ENCODING_SPEED: i32 = 10;       // 1 (best quality) - 30 (fastest)
+
+/// Configures which frames `OhCrabVisualizer::export_frames` renders and where they are written
+///
+pub struct FrameExportConfig {
+    output_dir: String,
+    png_ticks: HashSet<usize>,
+    gif_file_name: Option<String>,
+    tile_size: u32,
+    gif_frame_delay_ms: u32
+}
+
+impl FrameExportConfig {
+    pub fn new(output_dir: &str) -> Self {
+        FrameExportConfig {
+            output_dir: output_dir.to_owned(),
+            png_ticks: HashSet::new(),
+            gif_file_name: None,
+            tile_size: 16,
+            gif_frame_delay_ms: 100
+        }
+    }
+
+    /// World after every given tick is saved as `tick<N>.png`
+    pub fn with_png_ticks(mut self, ticks: &[usize]) -> Self {
+        self.png_ticks.extend(ticks);
+        self
+    }
+
+    /// Whole run is saved as animated GIF, one frame per tick
+    pub fn with_gif(mut self, gif_file_name: &str) -> Self {
+        self.gif_file_name = Some(gif_file_name.to_owned());
+        self
+    }
+
+    /// Size of one tile in pixels
+    pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    pub fn with_gif_frame_delay(mut self, delay_ms: u32) -> Self {
+        self.gif_frame_delay_ms = delay_ms;
+        self
+    }
+}
+
+/// Draws the world grid into an image without a window, uses the same tile colors and images as the window does
+///
+struct FrameRenderer {
+    tile_size: u32,
+    tile_images_dir: String,
+    content_images_dir: String,
+    tile_images: HashMap<String, Option<RgbaImage>>,    // loaded and resized lazily, None when the image is missing
+    content_images: HashMap<String, Option<RgbaImage>>
+}
+
+impl FrameRenderer {
+    fn new(tile_size: u32, resource_path: &str) -> FrameRenderer {
+        FrameRenderer {
+            tile_size,
+            tile_images_dir: format!("{}/images/tiles/", resource_path),
+            content_images_dir: format!("{}/images/content/", resource_path),
+            tile_images: HashMap::new(),
+            content_images: HashMap::new()
+        }
+    }
+
+    fn render(&mut self, world_map: &Vec<Vec<Tile>>, robots: &[RobotMarker<'_>]) -> RgbaImage {
+        let size = world_map.len() as u32 * self.tile_size;
+        let mut frame = RgbaImage::new(size, size);
+
+        for (y, row) in world_map.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                let tile_x = x as u32 * self.tile_size;
+                let tile_y = y as u32 * self.tile_size;
+                self.draw_tile(&mut frame, tile, tile_x, tile_y);
+            }
+        }
+
+        for robot in robots {
+            let center_x = (robot.position.x as f32 + 0.5) * self.tile_size as f32;
+            let center_y = (robot.position.y as f32 + 0.5) * self.tile_size as f32;
+            draw_circle(&mut frame, center_x, center_y, self.tile_size as f32 * 0.35, to_rgba(Color::WHITE));
+            draw_circle(&mut frame, center_x, center_y, self.tile_size as f32 * 0.25, to_rgba(robot.color));
+        }
+        frame
+    }
+
+    fn draw_tile(&mut self, frame: &mut RgbaImage, tile: &Tile, tile_x: u32, tile_y: u32) {
+        let tile_name = remove_content_between_parentheses(&format!("{:?}", tile.tile_type));
+        let tile_size = self.tile_size;
+        match load_image(&mut self.tile_images, &self.tile_images_dir, &tile_name, tile_size) {
+            Some(tile_image) => imageops::overlay(frame, tile_image, tile_x as i64, tile_y as i64),
+            None => draw_rect(frame, tile_x, tile_y, tile_size, to_rgba(get_tile_color(&tile.tile_type))),
+        }
+
+        if tile_size >= CONTENT_IMAGE_SIZE_LIMIT && tile.content != Content::None {
+            let content_name = remove_content_between_parentheses(&format!("{:?}", tile.content));
+            let content_size = (tile_size as f32 * 0.5) as u32;
+            if let Some(content_image) = load_image(&mut self.content_images, &self.content_images_dir, &content_name, content_size) {
+                imageops::overlay(frame, content_image, tile_x as i64, (tile_y + tile_size - content_size) as i64);
+            }
+        }
+    }
+}
+
+/// Renders frames of the run and writes them as PNG files and GIF animation
+///
+pub(super) struct FrameExporter {
+    renderer: FrameRenderer,
+    output_dir: String,
+    png_ticks: HashSet<usize>,
+    gif_encoder: Option<GifEncoder<BufWriter<File>>>,
+    gif_frame_delay_ms: u32
+}
+
+impl FrameExporter {
+    /// Images are loaded from `resource_path` the same way as in the window
+    pub(super) fn new(export_config: FrameExportConfig, resource_path: &str) -> Result<FrameExporter, OhCrabVisualizerError> {
+        fs::create_dir_all(&export_config.output_dir).map_err(|err| export_error(&export_config.output_dir, err))?;
+
+        let gif_encoder = match &export_config.gif_file_name {
+            Some(gif_file_name) => {
+                let gif_path = Path::new(&export_config.output_dir).join(gif_file_name);
+                let file = File::create(&gif_path).map_err(|err| export_error(&gif_path.to_string_lossy(), err))?;
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), GIF_ENCODING_SPEED);
+                encoder.set_repeat(Repeat::Infinite).map_err(|err| export_error(&gif_path.to_string_lossy(), err))?;
+                println!("Exporting animation to {}", gif_path.to_string_lossy());
+                Some(encoder)
+            }
+            None => None
+        };
+
+        Ok(FrameExporter {
+            renderer: FrameRenderer::new(export_config.tile_size, resource_path),
+            output_dir: export_config.output_dir,
+            png_ticks: export_config.png_ticks,
+            gif_encoder,
+            gif_frame_delay_ms: export_config.gif_frame_delay_ms
+        })
+    }
+
+    /// Frame is rendered only if it is needed for PNG or GIF
+    pub(super) fn export_frame(&mut self, tick: usize, world_map: &Vec<Vec<Tile>>, robots: &[RobotMarker<'_>]) -> Result<(), OhCrabVisualizerError> {
+        let save_png = self.png_ticks.contains(&tick);
+        if !save_png && self.gif_encoder.is_none() {
+            return Ok(());
+        }
+        println_d!("FRAME EXPORT: rendering tick {}", tick);
+        let frame = self.renderer.render(world_map, robots);
+
+        if save_png {
+            let png_path = Path::new(&self.output_dir).join(format!("tick{}.png", tick));
+            frame.save(&png_path).map_err(|err| export_error(&png_path.to_string_lossy(), err))?;
+            println!("Exported {}", png_path.to_string_lossy());
+        }
+        if let Some(encoder) = &mut self.gif_encoder {
+            let delay = Delay::from_numer_denom_ms(self.gif_frame_delay_ms, 1);
+            encoder.encode_frame(Frame::from_parts(frame, 0, 0, delay)).map_err(|err| export_error("animation", err))?;
+        }
+        Ok(())
+    }
+}
+
+fn load_image<'a>(cache: &'a mut HashMap<String, Option<RgbaImage>>, dir_name: &str, name: &str, size: u32) -> Option<&'a RgbaImage> {
+    cache.entry(name.to_owned()).or_insert_with(|| {
+        // image files are not named consistently, some of them are lowercase
+        let image = image::open(format!("{}{}.png", dir_name, name))
+            .or_else(|_| image::open(format!("{}{}.png", dir_name, name.to_lowercase())));
+        match image {
+            Ok(image) => Some(imageops::resize(&image.to_rgba8(), size, size, FilterType::Triangle)),
+            Err(_) => {
+                println!("Failed to load image {}{}", dir_name, name);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+fn draw_rect(frame: &mut RgbaImage, x: u32, y: u32, size: u32, color: Rgba<u8>) {
+    for pixel_y in y..u32::min(y + size, frame.height()) {
+        for pixel_x in x..u32::min(x + size, frame.width()) {
+            frame.put_pixel(pixel_x, pixel_y, color);
+        }
+    }
+}
+
+fn draw_circle(frame: &mut RgbaImage, center_x: f32, center_y: f32, radius: f32, color: Rgba<u8>) {
+    let min_x = f32::max(0.0, center_x - radius) as u32;
+    let min_y = f32::max(0.0, center_y - radius) as u32;
+    let max_x = u32::min(frame.width(), (center_x + radius).ceil() as u32);
+    let max_y = u32::min(frame.height(), (center_y + radius).ceil() as u32);
+    for pixel_y in min_y..max_y {
+        for pixel_x in min_x..max_x {
+            let dx = pixel_x as f32 + 0.5 - center_x;
+            let dy = pixel_y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                frame.put_pixel(pixel_x, pixel_y, color);
+            }
+        }
+    }
+}
+
+fn to_rgba(color: Color) -> Rgba<u8> {
+    let (r, g, b, a) = color.to_rgba();
+    Rgba([r, g, b, a])
+}
+
+fn export_error(path: &str, error: impl std::fmt::Display) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ExportError(format!("Frame export {}: {}", path, error))
+}
@@ -0,0 +1,117 @@
+use robotics_lib::world::tile::{Tile, Content};
+
+use super::{Coord, visualizer::HeatmapDisplayOptions};
+
+/// Tiles within this distance are counted into the content density of a tile
+const CONTENT_DENSITY_RADIUS: usize = 2;
+
+/// Per tile statistics of the whole run, drawn as heatmap overlays over the tile grid.
+/// Heat values are kept between frames and rebuilt only when the statistics, the robot map or the displayed heatmap change.
+///
+#[derive(Default)]
+pub(super) struct TileHeatmaps {
+    visits: Vec<Vec<usize>>,        // how many times robot moved to the tile
+    energy_spent: Vec<Vec<usize>>,  // energy consumed while robot stood on the tile
+    max_visits: usize,
+    max_energy_spent: usize,
+    heat_values: Option<Vec<Vec<f32>>>,
+    heat_values_source: Option<(HeatmapDisplayOptions, usize)>  // heatmap and content index the heat values were built for, None when outdated
+}
+
+impl TileHeatmaps {
+    pub(super) fn new(world_dimension: usize) -> TileHeatmaps {
+        TileHeatmaps {
+            visits: vec![vec![0; world_dimension]; world_dimension],
+            energy_spent: vec![vec![0; world_dimension]; world_dimension],
+            max_visits: 0,
+            max_energy_spent: 0,
+            heat_values: None,
+            heat_values_source: None
+        }
+    }
+
+    pub(super) fn record_visit(&mut self, coord: &Coord) {
+        if let Some(visits) = self.visits.get_mut(coord.y).and_then(|row| row.get_mut(coord.x)) {
+            *visits += 1;
+            self.max_visits = usize::max(self.max_visits, *visits);
+            self.invalidate_heat_values();
+        }
+    }
+
+    pub(super) fn record_energy_spent(&mut self, coord: &Coord, amount: usize) {
+        if let Some(energy_spent) = self.energy_spent.get_mut(coord.y).and_then(|row| row.get_mut(coord.x)) {
+            *energy_spent += amount;
+            self.max_energy_spent = usize::max(self.max_energy_spent, *energy_spent);
+            self.invalidate_heat_values();
+        }
+    }
+
+    /// Has to be called whenever the robot map changes, content density is computed from it
+    pub(super) fn invalidate_heat_values(&mut self) {
+        self.heat_values_source = None;
+    }
+
+    /// Rebuilds heat values when they are outdated or another heatmap or content was chosen, `content_index` is index to `heatmap_contents`
+    pub(super) fn update_heat_values(&mut self, heatmap_option: HeatmapDisplayOptions, content_index: usize, robot_map: &Option<Vec<Vec<Option<Tile>>>>) {
+        if self.heat_values_source == Some((heatmap_option, content_index)) {
+            return;
+        }
+        let heat_values = get_heat_values(&heatmap_option, self, robot_map, &heatmap_contents()[content_index]);
+        self.heat_values = heat_values;
+        self.heat_values_source = Some((heatmap_option, content_index));
+    }
+
+    /// Heat of every tile in range 0.0 - 1.0 indexed as `[y][x]` as of the last `update_heat_values`, None when no heatmap is selected
+    pub(super) fn heat_values(&self) -> &Option<Vec<Vec<f32>>> {
+        &self.heat_values
+    }
+}
+
+/// Contents user can choose for the content density heatmap
+pub(super) fn heatmap_contents() -> Vec<Content> {
+    vec![
+        Content::Rock(0), Content::Tree(0), Content::Garbage(0), Content::Fire, Content::Coin(0), Content::Bin(0..0), Content::Crate(0..0),
+        Content::Bank(0..0), Content::Water(0), Content::Market(0), Content::Fish(0), Content::Building, Content::Bush(0), Content::JollyBlock(0),
+        Content::Scarecrow
+    ]
+}
+
+/// Heat of every tile in range 0.0 - 1.0 indexed as `[y][x]`, None when no heatmap is selected
+fn get_heat_values(heatmap_option: &HeatmapDisplayOptions, heatmaps: &TileHeatmaps, robot_map: &Option<Vec<Vec<Option<Tile>>>>, content: &Content) -> Option<Vec<Vec<f32>>> {
+    match heatmap_option {
+        HeatmapDisplayOptions::No => None,
+        HeatmapDisplayOptions::Visits => Some(normalize(&heatmaps.visits, heatmaps.max_visits)),
+        HeatmapDisplayOptions::EnergySpent => Some(normalize(&heatmaps.energy_spent, heatmaps.max_energy_spent)),
+        HeatmapDisplayOptions::ContentDensity => robot_map.as_ref().map(|robot_map| get_content_density(robot_map, content)),
+    }
+}
+
+fn normalize(values: &Vec<Vec<usize>>, max_value: usize) -> Vec<Vec<f32>> {
+    values.iter()
+        .map(|row| row.iter().map(|value| if max_value == 0 { 0.0 } else { *value as f32 / max_value as f32 }).collect())
+        .collect()
+}
+
+/// Number of known tiles with given content around every tile, computed from summed-area table of the known map
+fn get_content_density(robot_map: &Vec<Vec<Option<Tile>>>, content: &Content) -> Vec<Vec<f32>> {
+    let world_dimension = robot_map.len();
+    let mut summed_area = vec![vec![0usize; world_dimension + 1]; world_dimension + 1];
+    for y in 0..world_dimension {
+        for x in 0..world_dimension {
+            let has_content = robot_map[y][x].as_ref().map_or(false, |tile| tile.content.index() == content.index());
+            summed_area[y + 1][x + 1] = has_content as usize + summed_area[y][x + 1] + summed_area[y + 1][x] - summed_area[y][x];
+        }
+    }
+
+    let mut density = vec![vec![0usize; world_dimension]; world_dimension];
+    let mut max_density = 0;
+    for y in 0..world_dimension {
+        for x in 0..world_dimension {
+            let (first_x, first_y) = (x.saturating_sub(CONTENT_DENSITY_RADIUS), y.saturating_sub(CONTENT_DENSITY_RADIUS));
+            let (last_x, last_y) = (usize::min(world_dimension, x + CONTENT_DENSITY_RADIUS + 1), usize::min(world_dimension, y + CONTENT_DENSITY_RADIUS + 1));
+            density[y][x] = summed_area[last_y][last_x] + summed_area[first_y][first_x] - summed_area[first_y][last_x] - summed_area[last_y][first_x];
+            max_density = usize::max(max_density, density[y][x]);
+        }
+    }
+    normalize(&density, max_density)
+}
@@ -0,0 +1,206 @@
+use std::{collections::{BTreeMap, HashMap}, io::Write, mem, net::{TcpListener, TcpStream}, sync::{Arc, Mutex}, thread, time::Duration};
+
+use robotics_lib::{event::events::Event as RobotEvent, world::tile::{Tile, Content}};
+use serde::Serialize;
+
+use super::{visualizer::OhCrabVisualizerError, visualizer_event_listener::{ChannelItem, RobotMapUpdate}, visualizable_robot::InitWorldState, recording::RecordingEntry, serializable::{SerializableChannelItem, SerializableInitState}};
+
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(200); // slower clients are disconnected so that they cannot stall the simulation
+
+/// One line of the live stream, entries are the same as the lines of a recording
+///
+#[derive(Serialize)]
+struct LiveStreamMessage<'a> {
+    robot: &'a str,
+    entry: &'a RecordingEntry
+}
+
+/// Current state of a streamed robot, clients connecting during the run get it instead of everything streamed since the start
+///
+#[derive(Default)]
+struct StreamedRobot {
+    init_state: Option<SerializableInitState>,  // world map, robot position and energy updated by the events of every tick
+    robot_map: Option<Vec<Vec<Option<Tile>>>>,
+    backpack: HashMap<Content, usize>,
+    environment: Option<RobotEvent>,            // the last `TimeChanged` or `DayChanged` event
+    score: Option<f32>,
+    tick: usize
+}
+
+impl StreamedRobot {
+    fn process_item(&mut self, item: &ChannelItem) {
+        match item {
+            ChannelItem::EventChannelItem(event) => self.process_event(event),
+            ChannelItem::RobotMapChannelItem(update) => update.apply(&mut self.robot_map),
+            ChannelItem::ScoreChannelItem(score) => self.score = Some(*score),
+            _ => {}
+        }
+    }
+
+    fn process_event(&mut self, event: &RobotEvent) {
+        match event {
+            RobotEvent::TimeChanged(_) | RobotEvent::DayChanged(_) => self.environment = Some(event.clone()),
+            RobotEvent::AddedToBackpack(content, amount) => *self.backpack.entry(content.clone()).or_insert(0) += amount,
+            RobotEvent::RemovedFromBackpack(content, amount) => {
+                if let Some(current_amount) = self.backpack.get_mut(content) {
+                    if *current_amount > *amount {
+                        *current_amount -= amount;
+                    } else {
+                        self.backpack.remove(content);
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(init_state) = &mut self.init_state {
+            match event {
+                RobotEvent::Moved(tile, (row, col)) => {
+                    init_state.world_map[*row][*col] = tile.clone();
+                    init_state.robot_position = (*col, *row);
+                }
+                RobotEvent::TileContentUpdated(tile, (row, col)) => init_state.world_map[*row][*col] = tile.clone(),
+                RobotEvent::EnergyConsumed(amount) => init_state.robot_energy = init_state.robot_energy.saturating_sub(*amount),
+                RobotEvent::EnergyRecharged(amount) => init_state.robot_energy += amount,
+                _ => {}
+            }
+        }
+    }
+
+    /// Current world as the initial state followed by items restoring the weather, robot map, backpack and score
+    fn catch_up_lines(&self, robot_name: &str) -> Vec<String> {
+        let init_state = match &self.init_state {
+            Some(init_state) => init_state.clone(),
+            None => return Vec::new(),
+        };
+        let mut items = Vec::new();
+        if let Some(environment) = &self.environment {
+            items.push(SerializableChannelItem::Event(environment.into()));
+        }
+        if let Some(robot_map) = &self.robot_map {
+            items.push(SerializableChannelItem::RobotMapUpdate((&RobotMapUpdate::from_maps(&None, robot_map)).into()));
+        }
+        for (content, amount) in self.backpack.iter() {
+            items.push(SerializableChannelItem::Event((&RobotEvent::AddedToBackpack(content.clone(), *amount)).into()));
+        }
+        if let Some(score) = self.score {
+            items.push(SerializableChannelItem::Score(score));
+        }
+
+        let mut lines = Vec::new();
+        lines.extend(to_line(robot_name, &RecordingEntry::InitState(init_state)));
+        for item in items {
+            lines.extend(to_line(robot_name, &RecordingEntry::TickItem { tick: self.tick, item }));
+        }
+        lines
+    }
+}
+
+#[derive(Default)]
+struct LiveStreamState {
+    clients: Vec<TcpStream>,
+    joining_clients: BTreeMap<usize, Vec<String>>,  // lines broadcast while the client is being caught up, by client id
+    next_client_id: usize,
+    robots: BTreeMap<String, StreamedRobot>
+}
+
+impl LiveStreamState {
+    fn broadcast(&mut self, line: &str) {
+        self.clients.retain_mut(|client| writeln!(client, "{}", line).and_then(|_| client.flush()).is_ok());
+        for queued_lines in self.joining_clients.values_mut() {
+            queued_lines.push(line.to_owned());
+        }
+    }
+
+    /// Client connecting during the run gets the current state of every robot first
+    fn catch_up_lines(&self) -> Vec<String> {
+        self.robots.iter().flat_map(|(robot_name, robot)| robot.catch_up_lines(robot_name)).collect()
+    }
+}
+
+/// Streams initial world states and all the channel items as JSON lines to every client connected to a local TCP socket,
+/// so that the simulation can be watched outside of the visualizer window, e.g. from a browser based viewer or a notebook.
+///
+#[derive(Clone)]
+pub(super) struct LiveStream {
+    state: Arc<Mutex<LiveStreamState>>
+}
+
+impl LiveStream {
+    /// Clients are accepted in a background thread for the whole run
+    pub(super) fn start(address: &str) -> Result<LiveStream, OhCrabVisualizerError> {
+        let listener = TcpListener::bind(address).map_err(|err| OhCrabVisualizerError::ConfigurationError(format!("Live stream {}: {}", address, err)))?;
+        println!("Streaming simulation on {}", address);
+
+        let live_stream = LiveStream { state: Arc::new(Mutex::new(LiveStreamState::default())) };
+        let accepting_stream = live_stream.clone();
+        thread::spawn(move || {
+            for client in listener.incoming() {
+                match client {
+                    Ok(client) => accepting_stream.add_client(client),
+                    Err(err) => println!("Live stream: accepting client failed: {}", err),
+                }
+            }
+        });
+        Ok(live_stream)
+    }
+
+    /// Client is written to without holding the lock, so that a slow client cannot stall publishing. Lines broadcast in the meantime
+    /// are queued for the client and written before it joins the other clients.
+    fn add_client(&self, mut client: TcpStream) {
+        let _ = client.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT));
+        let _ = client.set_nodelay(true);
+        let (client_id, mut lines) = {
+            let mut state = self.state.lock().expect("Live stream: lock poisoned");
+            let client_id = state.next_client_id;
+            state.next_client_id += 1;
+            state.joining_clients.insert(client_id, Vec::new());
+            (client_id, state.catch_up_lines())
+        };
+        loop {
+            let caught_up = lines.iter().all(|line| writeln!(client, "{}", line).is_ok()) && client.flush().is_ok();
+            let mut state = self.state.lock().expect("Live stream: lock poisoned");
+            if !caught_up {
+                state.joining_clients.remove(&client_id);
+                return;
+            }
+            let queued_lines = state.joining_clients.get_mut(&client_id).map(mem::take).unwrap_or_default();
+            if queued_lines.is_empty() {
+                state.joining_clients.remove(&client_id);
+                state.clients.push(client);
+                return;
+            }
+            lines = queued_lines;
+        }
+    }
+
+    pub(super) fn publish_init_state(&self, robot_name: &str, init_state: &InitWorldState) {
+        let init_state: SerializableInitState = init_state.into();
+        let line = to_line(robot_name, &RecordingEntry::InitState(init_state.clone()));
+        let mut state = self.state.lock().expect("Live stream: lock poisoned");
+        state.robots.entry(robot_name.to_owned()).or_default().init_state = Some(init_state);
+        if let Some(line) = line {
+            state.broadcast(&line);
+        }
+    }
+
+    /// Items are also applied to the current state of the robot kept for the clients connecting later
+    pub(super) fn publish_item(&self, robot_name: &str, tick: usize, item: &ChannelItem) {
+        let mut state = self.state.lock().expect("Live stream: lock poisoned");
+        let robot = state.robots.entry(robot_name.to_owned()).or_default();
+        robot.process_item(item);
+        robot.tick = tick;
+        if let Some(line) = to_line(robot_name, &RecordingEntry::TickItem { tick, item: SerializableChannelItem::from_channel_item(item) }) {
+            state.broadcast(&line);
+        }
+    }
+}
+
+fn to_line(robot_name: &str, entry: &RecordingEntry) -> Option<String> {
+    match serde_json::to_string(&LiveStreamMessage { robot: robot_name, entry }) {
+        Ok(line) => Some(line),
+        Err(err) => {
+            println!("Live stream: serializing entry failed: {}", err);
+            None
+        }
+    }
+}
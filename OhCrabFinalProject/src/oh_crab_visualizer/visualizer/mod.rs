@@ -4,10 +4,26 @@ pub mod visualizer;
 mod draw_utils;
 mod egui_utils;
 pub mod visualizer_event_listener;
+pub mod event_sink;
 mod visualizer_debug;
+mod serializable;
+mod recording;
+mod live_stream;
+mod world_history;
+mod robot_session;
+pub mod batch_simulation;
+mod time_series;
+mod heatmap;
+mod action_inference;
+mod action_history;
+mod playback;
+pub mod breakpoints;
+pub mod frame_export;
+pub mod settings;
+pub mod robot_registry;
 
 // Coordinate struct from robotic-lib does not allow for its instances to be created
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct Coord {
     pub(crate) x: usize, 
     pub(crate) y: usize
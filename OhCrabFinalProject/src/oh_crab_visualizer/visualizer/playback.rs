@@ -0,0 +1,91 @@
+use std::time::{Instant, Duration};
+
+use super::visualizer::RunMode;
+
+pub(super) const MIN_TICKS_PER_SECOND: f32 = 0.5;
+pub(super) const MAX_TICKS_PER_SECOND: f32 = 60.0;
+const DEFAULT_TICKS_PER_SECOND: f32 = 5.0;
+
+/// Decides when the visualizer does the next world tick. `RunMode` only sets the initial state,
+/// user can pause, resume and change the speed at runtime.
+///
+pub(super) struct Playback {
+    pub(super) is_playing: bool,
+    pub(super) ticks_per_second: f32,
+    pub(super) unlimited_speed: bool,           // tick as soon as the previous tick is processed
+    pub(super) run_until_tick: Option<usize>,   // playback is paused once this tick is reached
+    pub(super) run_until_input: usize,
+    pub(super) halted_at_breakpoint: bool,      // no more items are processed until user continues
+    resume_playing: bool,
+    last_tick_start: Option<Instant>
+}
+
+impl Playback {
+    pub(super) fn new(run_mode: &RunMode) -> Playback {
+        let is_playing = match run_mode {
+            RunMode::Interactive => false,
+            RunMode::NonInteractive(_) => true,
+        };
+        Playback {
+            is_playing,
+            ticks_per_second: DEFAULT_TICKS_PER_SECOND,
+            unlimited_speed: is_playing, // keeps the original speed of non-interactive runs
+            run_until_tick: None,
+            run_until_input: 0,
+            halted_at_breakpoint: false,
+            resume_playing: false,
+            last_tick_start: None
+        }
+    }
+
+    pub(super) fn toggle(&mut self) {
+        self.is_playing = !self.is_playing;
+        if !self.is_playing {
+            self.run_until_tick = None;
+        }
+    }
+
+    pub(super) fn halt_at_breakpoint(&mut self) {
+        if !self.halted_at_breakpoint {
+            self.resume_playing = self.is_playing;
+        }
+        self.halted_at_breakpoint = true;
+        self.is_playing = false;
+    }
+
+    /// Processing continues, autoplay is resumed only if it was running when the breakpoint was hit
+    pub(super) fn continue_from_breakpoint(&mut self) {
+        self.halted_at_breakpoint = false;
+        self.is_playing = self.resume_playing;
+    }
+
+    pub(super) fn run_until(&mut self, tick: usize) {
+        self.run_until_tick = Some(tick);
+        self.is_playing = true;
+    }
+
+    pub(super) fn tick_started(&mut self) {
+        self.last_tick_start = Some(Instant::now());
+    }
+
+    /// `tick_counter` is the number of ticks done so far, must be called only when no tick is in progress
+    pub(super) fn should_tick(&mut self, tick_counter: usize) -> bool {
+        if !self.is_playing {
+            return false;
+        }
+        if let Some(run_until_tick) = self.run_until_tick {
+            if tick_counter >= run_until_tick {
+                self.is_playing = false;
+                self.run_until_tick = None;
+                return false;
+            }
+        }
+        if self.unlimited_speed {
+            return true;
+        }
+        match self.last_tick_start {
+            Some(last_tick_start) => last_tick_start.elapsed() >= Duration::from_secs_f32(1.0 / self.ticks_per_second),
+            None => true,
+        }
+    }
+}
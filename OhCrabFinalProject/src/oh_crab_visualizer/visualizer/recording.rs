@@ -0,0 +1,115 @@
+use std::{fs::{self, File}, io::{BufWriter, Write, BufReader, BufRead}, collections::BTreeMap, path::Path, sync::mpsc::Sender};
+
+use serde::{Serialize, Deserialize};
+
+use crate::println_d;
+
+use super::{visualizer::OhCrabVisualizerError, visualizer_event_listener::ChannelItem, visualizable_robot::{InitWorldState, InitStateChannelItem}, serializable::{SerializableInitState, SerializableChannelItem}};
+
+/// One line of a recording file. Recording is stored as JSON lines,
+/// first line contains the initial state, every other line one channel item received during given tick
+///
+#[derive(Serialize, Deserialize)]
+pub(super) enum RecordingEntry {
+    InitState(SerializableInitState),
+    TickItem { tick: usize, item: SerializableChannelItem }
+}
+
+/// Writes the initial world state and the stream of channel items received by visualizer into a file.
+/// Every entry is flushed immediately so that the recording of a crashed simulation is usable as well.
+///
+pub(super) struct SimulationRecorder {
+    writer: BufWriter<File>
+}
+
+impl SimulationRecorder {
+    pub(super) fn new(recording_path: &str) -> Result<SimulationRecorder, OhCrabVisualizerError> {
+        if let Some(parent) = Path::new(recording_path).parent() {
+            fs::create_dir_all(parent).map_err(|err| recording_error(recording_path, err))?;
+        }
+        let file = File::create(recording_path).map_err(|err| recording_error(recording_path, err))?;
+        println!("Recording simulation to {}", recording_path);
+        Ok(SimulationRecorder { writer: BufWriter::new(file) })
+    }
+
+    pub(super) fn record_init_state(&mut self, init_state: &InitWorldState) -> Result<(), OhCrabVisualizerError> {
+        self.write_entry(&RecordingEntry::InitState(init_state.into()))
+    }
+
+    pub(super) fn record_item(&mut self, tick: usize, item: &ChannelItem) -> Result<(), OhCrabVisualizerError> {
+        self.write_entry(&RecordingEntry::TickItem { tick, item: SerializableChannelItem::from_channel_item(item) })
+    }
+
+    fn write_entry(&mut self, entry: &RecordingEntry) -> Result<(), OhCrabVisualizerError> {
+        let line = serde_json::to_string(entry).map_err(|err| OhCrabVisualizerError::RecordingError(format!("Serializing recording entry failed: {}", err)))?;
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|err| OhCrabVisualizerError::RecordingError(format!("Writing recording entry failed: {}", err)))
+    }
+}
+
+/// Plays previously recorded simulation back by sending recorded items to the visualizer channels,
+/// replaces `Runner` when visualizer is created by `OhCrabVisualizer::replay`
+///
+pub(super) struct SimulationReplay {
+    init_state: Option<InitWorldState>,
+    ticks: BTreeMap<usize, Vec<SerializableChannelItem>>,
+    last_tick: usize,
+    next_tick: usize,
+    item_sender: Sender<ChannelItem>,
+    map_sender: Sender<InitStateChannelItem>
+}
+
+impl SimulationReplay {
+    pub(super) fn load(recording_path: &str, item_sender: Sender<ChannelItem>, map_sender: Sender<InitStateChannelItem>) -> Result<SimulationReplay, OhCrabVisualizerError> {
+        println!("Loading recording {}", recording_path);
+        let file = File::open(recording_path).map_err(|err| recording_error(recording_path, err))?;
+
+        let mut init_state = None;
+        let mut ticks: BTreeMap<usize, Vec<SerializableChannelItem>> = BTreeMap::new();
+        for (line_number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|err| recording_error(recording_path, err))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: RecordingEntry = serde_json::from_str(&line)
+                .map_err(|err| OhCrabVisualizerError::RecordingError(format!("Recording {} is corrupted on line {}: {}", recording_path, line_number + 1, err)))?;
+            match entry {
+                RecordingEntry::InitState(state) => init_state = Some(state.into()),
+                RecordingEntry::TickItem { tick, item } => ticks.entry(tick).or_default().push(item),
+            }
+        }
+
+        if init_state.is_none() {
+            return Err(OhCrabVisualizerError::RecordingError(format!("Recording {} does not contain initial world state", recording_path)));
+        }
+        let last_tick = ticks.keys().next_back().copied().unwrap_or(0);
+        println_d!("REPLAY: loaded {} ticks", last_tick);
+
+        Ok(SimulationReplay { init_state, ticks, last_tick, next_tick: 1, item_sender, map_sender })
+    }
+
+    /// Sends items recorded for the next tick to the visualizer, initial state is sent together with the first tick
+    pub(super) fn replay_tick(&mut self) -> Result<(), OhCrabVisualizerError> {
+        if let Some(init_state) = self.init_state.take() {
+            self.map_sender.send(InitStateChannelItem { state: init_state })
+                .map_err(|_| OhCrabVisualizerError::RecordingError("Sending recorded initial state failed".to_string()))?;
+        }
+        if let Some(items) = self.ticks.remove(&self.next_tick) {
+            for item in items {
+                self.item_sender.send(item.into_channel_item())
+                    .map_err(|_| OhCrabVisualizerError::RecordingError("Sending recorded item failed".to_string()))?;
+            }
+        }
+        self.next_tick += 1;
+        Ok(())
+    }
+
+    pub(super) fn is_finished(&self) -> bool {
+        self.next_tick > self.last_tick
+    }
+}
+
+fn recording_error(recording_path: &str, error: std::io::Error) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::RecordingError(format!("Recording file {}: {}", recording_path, error))
+}
@@ -0,0 +1,116 @@
+use std::{collections::{BTreeMap, HashMap}, str::FromStr};
+
+use super::{visualizable_robot::RobotCreator, visualizer::OhCrabVisualizerError};
+
+type RobotFactoryFn = Box<dyn Fn(&RobotParams) -> Result<Box<dyn RobotCreator>, OhCrabVisualizerError>>;
+
+/// Parameters of a robot given as `key=value` pairs, e.g. from command line
+///
+#[derive(Default, Debug, Clone)]
+pub struct RobotParams {
+    values: HashMap<String, String>
+}
+
+impl RobotParams {
+    /// Parses `key=value` pairs, value of a repeated key is overwritten
+    pub fn parse(pairs: &[String]) -> Result<RobotParams, OhCrabVisualizerError> {
+        let mut values = HashMap::new();
+        for pair in pairs {
+            match pair.split_once('=') {
+                Some((key, value)) => { values.insert(key.trim().to_owned(), value.trim().to_owned()); }
+                None => return Err(OhCrabVisualizerError::ConfigurationError(format!("Robot parameter {} is not in key=value format", pair))),
+            }
+        }
+        Ok(RobotParams { values })
+    }
+
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.values.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Parameter parsed to `T`, `default` when the parameter is not given
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, OhCrabVisualizerError> {
+        match self.values.get(key) {
+            Some(value) => value.parse().map_err(|_| param_error(key, value)),
+            None => Ok(default),
+        }
+    }
+
+    /// Comma separated list, `default` when the parameter is not given
+    pub fn get_list_or(&self, key: &str, default: &[&str]) -> Vec<String> {
+        match self.values.get(key) {
+            Some(value) => value.split(',').map(|item| item.trim().to_owned()).filter(|item| !item.is_empty()).collect(),
+            None => default.iter().map(|item| item.to_string()).collect(),
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.values.keys()
+    }
+}
+
+struct RobotRegistration {
+    description: String,
+    params: Vec<(String, String)>,  // name and description of every accepted parameter
+    factory: RobotFactoryFn
+}
+
+/// Named robot factories, robots registered here can be launched by name from the binary and used by `BatchSimulation`
+///
+#[derive(Default)]
+pub struct RobotRegistry {
+    robots: BTreeMap<String, RobotRegistration>
+}
+
+impl RobotRegistry {
+    pub fn new() -> RobotRegistry {
+        RobotRegistry::default()
+    }
+
+    /// `params` lists names and descriptions of the parameters `factory` reads, other parameters are rejected
+    pub fn register(
+        mut self,
+        name: &str,
+        description: &str,
+        params: &[(&str, &str)],
+        factory: impl Fn(&RobotParams) -> Result<Box<dyn RobotCreator>, OhCrabVisualizerError> + 'static
+    ) -> Self {
+        let registration = RobotRegistration {
+            description: description.to_owned(),
+            params: params.iter().map(|(param, param_description)| (param.to_string(), param_description.to_string())).collect(),
+            factory: Box::new(factory)
+        };
+        self.robots.insert(name.to_owned(), registration);
+        self
+    }
+
+    pub fn create(&self, name: &str, params: &RobotParams) -> Result<Box<dyn RobotCreator>, OhCrabVisualizerError> {
+        let registration = self.robots.get(name).ok_or_else(|| OhCrabVisualizerError::ConfigurationError(
+            format!("Unknown robot {}, registered robots: {}", name, self.names().join(", "))))?;
+        if let Some(unknown_param) = params.keys().find(|key| !registration.params.iter().any(|(param, _)| param == *key)) {
+            return Err(OhCrabVisualizerError::ConfigurationError(format!("Robot {} does not accept parameter {}", name, unknown_param)));
+        }
+        (registration.factory)(params)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.robots.keys().cloned().collect()
+    }
+
+    /// Human readable list of the robots and their parameters
+    pub fn describe(&self) -> String {
+        let mut description = String::new();
+        for (name, registration) in self.robots.iter() {
+            description.push_str(&format!("{} - {}\n", name, registration.description));
+            for (param, param_description) in registration.params.iter() {
+                description.push_str(&format!("    {}: {}\n", param, param_description));
+            }
+        }
+        description
+    }
+}
+
+fn param_error(key: &str, value: &str) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ConfigurationError(format!("Robot parameter {} has invalid value {}", key, value))
+}
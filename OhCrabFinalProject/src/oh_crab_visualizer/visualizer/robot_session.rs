@@ -0,0 +1,538 @@
+use std::{sync::mpsc::{Receiver, self}, collections::{HashMap, VecDeque}};
+
+use oxagworldgenerator::world_generator::OxAgWorldGenerator;
+use rand::{rngs::ThreadRng, seq::SliceRandom};
+use robotics_lib::{runner::Runner, event::events::Event as RobotEvent, world::{tile::{Tile, Content}, environmental_conditions::EnvironmentalConditions}};
+
+use crate::println_d;
+
+use super::{visualizable_robot::{VisualizableRobot, RobotCreator, InitStateChannelItem, InitWorldState}, Coord, visualizer_event_listener::{VisualizerEventListener, ChannelItem, InterfaceInvocation, RobotPlan, RobotMapUpdate}, event_sink::EventSink, recording::{SimulationRecorder, SimulationReplay}, live_stream::LiveStream, settings::VisualizerSettings, world_history::WorldHistory, time_series::TimeSeries, heatmap::TileHeatmaps, action_inference::ActionInference, action_history::ActionHistory, breakpoints::Breakpoints, visualizer::{OhCrabVisualizerError, DataChannelError, WorldTime, HeatmapDisplayOptions}};
+
+/// Colors used to distinguish robots, the first one is used when a single robot is visualized
+pub(super) const ROBOT_COLORS: [(u8, u8, u8); 6] = [
+    (0, 0, 0),
+    (220, 20, 60),
+    (30, 144, 255),
+    (255, 140, 0),
+    (148, 0, 211),
+    (0, 128, 0)
+];
+
+/// Number of last robot positions displayed as the breadcrumb trail
+const BREADCRUMB_TRAIL_LENGTH: usize = 200;
+
+/// Where the world ticks come from
+///
+enum SimulationSource {
+    Live(Runner),              // robot is simulated by robotic lib runner
+    Replay(SimulationReplay)   // previously recorded simulation is played back
+}
+
+/// Represents state of robotic lib world as it is known to visualizer
+///
+pub(super) struct WorldState {
+    pub(super) world_map: Option<Vec<Vec<Tile>>>,
+    pub(super) robot_map: Option<Vec<Vec<Option<Tile>>>>,
+    pub(super) robot_position: Option<Coord>,
+    pub(super) backpack: HashMap<Content, usize>,
+    pub(super) robot_energy: usize,
+    pub(super) previous_tick_energy_difference: i32,
+    pub(super) current_tick_energy_difference: i32,
+    pub(super) rizler_message: Option<String>,
+    pub(super) score: f32,
+    pub(super) robot_plan: RobotPlan,
+    pub(super) travelled_path: VecDeque<Coord>,
+    rizzler_messages: Vec<String>
+}
+
+impl WorldState {
+    fn empty() -> WorldState {
+        WorldState {
+            world_map: None,
+            robot_map: None,
+            robot_position: None,
+            backpack: HashMap::new(),
+            robot_energy: 0,
+            current_tick_energy_difference: 0,
+            previous_tick_energy_difference: 0,
+            rizler_message: None,
+            score: 0.0,
+            robot_plan: RobotPlan::default(),
+            travelled_path: VecDeque::new(),
+            rizzler_messages: Vec::new()
+        }
+    }
+
+    fn snapshot(&self, world_time: &WorldTime) -> TickSnapshot {
+        TickSnapshot {
+            robot_position: self.robot_position.clone(),
+            backpack: self.backpack.clone(),
+            robot_energy: self.robot_energy,
+            previous_tick_energy_difference: self.previous_tick_energy_difference,
+            current_tick_energy_difference: self.current_tick_energy_difference,
+            rizler_message: self.rizler_message.clone(),
+            score: self.score,
+            robot_plan: self.robot_plan.clone(),
+            travelled_path: self.travelled_path.clone(),
+            rizzler_messages: self.rizzler_messages.clone(),
+            world_time: world_time.clone()
+        }
+    }
+
+    fn restore(&mut self, snapshot: TickSnapshot, world_time: &mut WorldTime) {
+        self.robot_position = snapshot.robot_position;
+        self.backpack = snapshot.backpack;
+        self.robot_energy = snapshot.robot_energy;
+        self.previous_tick_energy_difference = snapshot.previous_tick_energy_difference;
+        self.current_tick_energy_difference = snapshot.current_tick_energy_difference;
+        self.rizler_message = snapshot.rizler_message;
+        self.score = snapshot.score;
+        self.robot_plan = snapshot.robot_plan;
+        self.travelled_path = snapshot.travelled_path;
+        self.rizzler_messages = snapshot.rizzler_messages;
+        *world_time = snapshot.world_time;
+    }
+}
+
+/// Part of the world state stored in `WorldHistory` for every tick, maps are tracked by the history itself
+///
+#[derive(Clone)]
+pub(super) struct TickSnapshot {
+    robot_position: Option<Coord>,
+    backpack: HashMap<Content, usize>,
+    robot_energy: usize,
+    previous_tick_energy_difference: i32,
+    current_tick_energy_difference: i32,
+    rizler_message: Option<String>,
+    score: f32,
+    robot_plan: RobotPlan,
+    travelled_path: VecDeque<Coord>,
+    rizzler_messages: Vec<String>,
+    world_time: WorldTime
+}
+
+/// Everything visualizer keeps about a single robot - its simulation, channels and the state of its world
+///
+pub(super) struct RobotSession {
+    pub(super) name: String,
+    pub(super) color: (u8, u8, u8),
+    simulation_source: SimulationSource,
+    robot_receiver: Receiver<ChannelItem>,
+    map_receiver: Receiver<InitStateChannelItem>,
+    recorder: Option<SimulationRecorder>,
+    live_stream: Option<LiveStream>,
+    pub(super) action_history: ActionHistory,
+    action_inference: ActionInference,
+    pub(super) world_state: WorldState,
+    pub(super) world_time: WorldTime,
+    pub(super) world_history: WorldHistory<TickSnapshot>,
+    pub(super) time_series: TimeSeries,
+    pub(super) heatmaps: TileHeatmaps,
+    pub(super) world_tick_in_progress: bool
+}
+
+impl RobotSession {
+    pub(super) fn live(name: String, color: (u8, u8, u8), robot_creator: &dyn RobotCreator, world_generator: &mut OxAgWorldGenerator, settings: &VisualizerSettings, use_sound: bool, recording_path: Option<String>, event_sinks: Vec<Box<dyn EventSink>>) -> Result<RobotSession, OhCrabVisualizerError> {
+        let (robot_sender, robot_receiver) = mpsc::channel::<ChannelItem>();
+        let (map_sender, map_receiver) = mpsc::channel::<InitStateChannelItem>();
+
+        let mut visualizer_data_sender = VisualizerEventListener::new(robot_sender, if use_sound { Some(settings) } else { None });
+        for event_sink in event_sinks {
+            visualizer_data_sender.add_sink(event_sink);
+        }
+        let robot = robot_creator.create_forwarding(visualizer_data_sender);
+        let visualizable_robot = VisualizableRobot::new(robot, map_sender);
+
+        let runner = Runner::new(Box::new(visualizable_robot), world_generator).map_err(OhCrabVisualizerError::RobotLibError)?;
+
+        RobotSession::build(name, color, SimulationSource::Live(runner), robot_receiver, map_receiver, settings, recording_path)
+    }
+
+    pub(super) fn replay(name: String, color: (u8, u8, u8), replay_path: &str, settings: &VisualizerSettings, recording_path: Option<String>) -> Result<RobotSession, OhCrabVisualizerError> {
+        let (robot_sender, robot_receiver) = mpsc::channel::<ChannelItem>();
+        let (map_sender, map_receiver) = mpsc::channel::<InitStateChannelItem>();
+
+        let replay = SimulationReplay::load(replay_path, robot_sender, map_sender)?;
+        RobotSession::build(name, color, SimulationSource::Replay(replay), robot_receiver, map_receiver, settings, recording_path)
+    }
+
+    fn build(name: String, color: (u8, u8, u8), simulation_source: SimulationSource, robot_receiver: Receiver<ChannelItem>, map_receiver: Receiver<InitStateChannelItem>, settings: &VisualizerSettings, recording_path: Option<String>) -> Result<RobotSession, OhCrabVisualizerError> {
+        let recorder = match recording_path {
+            Some(path) => Some(SimulationRecorder::new(&path)?),
+            None => None,
+        };
+
+        Ok(RobotSession {
+            name,
+            color,
+            simulation_source,
+            robot_receiver,
+            map_receiver,
+            recorder,
+            live_stream: None,
+            action_history: ActionHistory::new(settings.history_cache_length),
+            action_inference: ActionInference::default(),
+            world_state: WorldState::empty(),
+            world_time: WorldTime::default(),
+            world_history: WorldHistory::new(),
+            time_series: TimeSeries::default(),
+            heatmaps: TileHeatmaps::default(),
+            world_tick_in_progress: false
+        })
+    }
+
+    /// Ticks the world without processing the channels, used by `OhCrabVisualizer::simulate`
+    pub(super) fn simulate_tick(&mut self, tick_counter: usize) -> Result<(), OhCrabVisualizerError> {
+        match &mut self.simulation_source {
+            SimulationSource::Live(runner) => {
+                match runner.game_tick() {
+                    Ok(_) => {}
+                    Err(robot_err) => { return Err(OhCrabVisualizerError::RobotLibError(robot_err)); }
+                }
+            }
+            SimulationSource::Replay(_) => {
+                return Err(OhCrabVisualizerError::ConfigurationError("Cannot run simulation of a replay. To play the recording back, use run method.".to_string()));
+            }
+        }
+        self.record_simulated_tick(tick_counter)
+    }
+
+    /// Simulation does not process the channels, items of the last tick are only passed to the recorder (if any)
+    fn record_simulated_tick(&mut self, tick_counter: usize) -> Result<(), OhCrabVisualizerError> {
+        if let Ok(init_state_item) = self.map_receiver.try_recv() {
+            self.record_init_state(&init_state_item.state)?;
+        }
+        while let Ok(channel_item) = self.robot_receiver.try_recv() {
+            self.record_item(tick_counter, &channel_item)?;
+        }
+        Ok(())
+    }
+
+    /// Initial state and items are streamed as they are received, in addition to the recording
+    pub(super) fn attach_live_stream(&mut self, live_stream: LiveStream) {
+        self.live_stream = Some(live_stream);
+    }
+
+    fn record_init_state(&mut self, init_state: &InitWorldState) -> Result<(), OhCrabVisualizerError> {
+        if let Some(live_stream) = &self.live_stream {
+            live_stream.publish_init_state(&self.name, init_state);
+        }
+        match &mut self.recorder {
+            Some(recorder) => recorder.record_init_state(init_state),
+            None => Ok(())
+        }
+    }
+
+    fn record_item(&mut self, tick_counter: usize, channel_item: &ChannelItem) -> Result<(), OhCrabVisualizerError> {
+        if let Some(live_stream) = &self.live_stream {
+            live_stream.publish_item(&self.name, tick_counter, channel_item);
+        }
+        match &mut self.recorder {
+            Some(recorder) => recorder.record_item(tick_counter, channel_item),
+            None => Ok(())
+        }
+    }
+
+    fn update_riz_messages_for_tick(&mut self, rng: &mut ThreadRng) {
+        match self.world_state.rizzler_messages.choose(rng) {
+            Some(message) => {self.world_state.rizler_message = Some(message.clone())},
+            None => {},
+        }
+        self.world_state.rizzler_messages.clear();
+    }
+
+    fn update_energy_difference_for_tick(&mut self) {
+        self.world_state.previous_tick_energy_difference = self.world_state.current_tick_energy_difference;
+        self.world_state.current_tick_energy_difference = 0;
+    }
+
+    /// `tick_counter` is the number of ticks done before this one
+    pub(super) fn do_world_tick(&mut self, tick_counter: usize, rng: &mut ThreadRng) -> Result<(), OhCrabVisualizerError> {
+        if tick_counter > 0 {
+            self.world_history.commit_tick(self.world_state.snapshot(&self.world_time));
+            let backpack_fill = self.world_state.backpack.values().sum();
+            self.time_series.commit_tick(tick_counter, self.world_state.robot_energy, self.world_state.score, backpack_fill);
+        }
+        self.update_energy_difference_for_tick();
+        self.update_riz_messages_for_tick(rng);
+        let res = match &mut self.simulation_source {
+            SimulationSource::Live(runner) => runner.game_tick(),
+            SimulationSource::Replay(replay) => {
+                replay.replay_tick()?;
+                Ok(())
+            }
+        };
+        match res {
+            Ok(_) => {
+                self.world_tick_in_progress = true;
+                Ok(())
+            },
+            Err(robot_err) => { return Err(OhCrabVisualizerError::RobotLibError(robot_err)); }
+        }
+    }
+
+    /// Receives the initial world state sent during the first tick, returns dimension of the world
+    pub(super) fn init_state(&mut self) -> Result<usize, OhCrabVisualizerError> {
+        let received_map = self.map_receiver.try_recv();
+
+        match received_map {
+            Ok(item) => {
+                self.record_init_state(&item.state)?;
+                let robot_pos = item.state.robot_position;
+                println_d!("VISUALIZER RECEIVED MAP of {} with robot position {:?} and robot energy {:?}", self.name, (robot_pos.x, robot_pos.y), item.state.robot_energy);
+                let world_dimension = item.state.world_map.len();
+                self.heatmaps = TileHeatmaps::new(world_dimension);
+                self.heatmaps.record_visit(&robot_pos);
+                self.world_state.world_map = Some(item.state.world_map);
+                self.world_state.robot_position = Some(robot_pos);
+                self.action_inference.set_robot_position((robot_pos.y, robot_pos.x));
+                self.world_state.robot_energy = item.state.robot_energy;
+                Ok(world_dimension)
+            }
+            Err(_) => Err(OhCrabVisualizerError::DataError(DataChannelError::StateMissingError(format!("Initial state of {} was not received", self.name)))),
+        }
+    }
+
+    pub(super) fn is_replay(&self) -> bool {
+        matches!(self.simulation_source, SimulationSource::Replay(_))
+    }
+
+    pub(super) fn replay_finished(&self) -> bool {
+        match &self.simulation_source {
+            SimulationSource::Live(_) => false,
+            SimulationSource::Replay(replay) => replay.is_finished() && !self.world_tick_in_progress,
+        }
+    }
+
+    pub(super) fn go_to_tick(&mut self, target_tick: usize) {
+        println_d!("VISUALIZER: moving {} to tick {}", self.name, target_tick);
+        let live_snapshot = if self.world_history.is_live() { Some(self.world_state.snapshot(&self.world_time)) } else { None };
+        if let Some(world_map) = &mut self.world_state.world_map {
+            let snapshot = self.world_history.go_to_tick(target_tick, live_snapshot, world_map, &mut self.world_state.robot_map);
+            if let Some(snapshot) = snapshot {
+                self.world_state.restore(snapshot, &mut self.world_time);
+            }
+            self.heatmaps.invalidate_heat_values();
+        }
+    }
+
+    /// Heat values drawn over the tile grid are rebuilt only when something they depend on changed
+    pub(super) fn update_heat_values(&mut self, heatmap_option: HeatmapDisplayOptions, content_index: usize) {
+        self.heatmaps.update_heat_values(heatmap_option, content_index, &self.world_state.robot_map);
+    }
+
+    #[inline]
+    fn process_time_changed_event(&mut self, env_conditions: EnvironmentalConditions) {
+        println_d!("VISUALIZER: received EVENT time changed {:?}", (env_conditions));
+        self.world_time.update_from_env_conditions(&env_conditions);
+    }
+
+    #[inline]
+    fn process_day_changed_event(&mut self, env_conditions: EnvironmentalConditions) {
+        println_d!("VISUALIZER: received EVENT day changed {:?}", (env_conditions));
+        self.world_time.update_from_env_conditions(&env_conditions);
+        self.world_time.day_counter +=1;
+    }
+
+    #[inline]
+    fn process_energy_regarged_event(&mut self, amount: usize) {
+        println_d!("VISUALIZER: received EVENT energy recharged {:?}", (amount));
+        self.world_state.robot_energy += amount;
+        self.world_state.current_tick_energy_difference += amount as i32;
+        self.time_series.record_energy_recharged(amount);
+    }
+
+    #[inline]
+    fn process_energy_consumed_event(&mut self, amount: usize) {
+        println_d!("VISUALIZER: received EVENT energy consumed {:?}", (amount));
+        let to_subtract = usize::min(amount, self.world_state.robot_energy); // to prevent subtract with overflow
+        self.world_state.robot_energy -= to_subtract;
+        self.world_state.current_tick_energy_difference -= to_subtract as i32;
+        self.time_series.record_energy_consumed(amount);
+        if let Some(robot_position) = &self.world_state.robot_position {
+            self.heatmaps.record_energy_spent(robot_position, amount);
+        }
+    }
+
+    #[inline]
+    fn process_moved_event(&mut self, robot_x: usize, robot_y :usize) {
+        println_d!("VISUALIZER: received robot moved {:?}", (robot_x, robot_y));
+        let robot_position = Coord{x:robot_x, y:robot_y };
+
+        // breadcrumb trail
+        if let Some(previous_position) = self.world_state.robot_position.take() {
+            self.world_state.travelled_path.push_back(previous_position);
+            if self.world_state.travelled_path.len() > BREADCRUMB_TRAIL_LENGTH {
+                self.world_state.travelled_path.pop_front();
+            }
+        }
+
+        // walked part of the plan is not displayed anymore
+        if let Some(reached_index) = self.world_state.robot_plan.path.iter().position(|coord| *coord == robot_position) {
+            self.world_state.robot_plan.path.drain(..reached_index);
+        }
+        self.heatmaps.record_visit(&robot_position);
+        self.world_state.robot_position = Some(robot_position);
+    }
+
+    #[inline]
+    fn process_tile_content_update_event(&mut self, tile: Tile, tile_x: usize, tile_y: usize) {
+        println_d!("VISUALIZER: received tile content update.");
+        if let Some(world_map) = &mut self.world_state.world_map{
+            let old_tile = std::mem::replace(&mut world_map[tile_y][tile_x], tile.clone());
+            self.world_history.record_world_tile_change(tile_x, tile_y, old_tile, tile);
+        }
+    }
+
+    #[inline]
+    fn process_added_to_backpack_event(&mut self, content: Content, amount: usize) {
+        println_d!("VISUALIZER: added to backpack: {:?}, {:?}.", content, amount);
+        *self.world_state.backpack.entry(content.clone()).or_insert(0) += amount;
+        println_d!("   current amount {:?} of {:?} after add", self.world_state.backpack.get(&content), content.to_string());
+    }
+
+    #[inline]
+    fn process_removed_from_backpack_event(&mut self,  content: Content, amount: usize) {
+        println_d!("VISUALIZER: removed from backpack: {:?}, {:?}.", content, amount);
+        if let Some(current_amount) = self.world_state.backpack.get_mut(&content) {
+            if *current_amount > amount {
+                *current_amount -= amount;
+                println_d!("   current amount {:?} of {:?} after remove", *current_amount, content.to_string());
+            } else {
+                self.world_state.backpack.remove(&content);
+            }
+        }
+    }
+
+    #[inline]
+    fn process_robot_map_record(&mut self, robot_map_update: RobotMapUpdate) {
+        println_d!("VISUALIZER: received robot map update.");
+        let dimension = robot_map_update.world_dimension;
+        let robot_map = self.world_state.robot_map.get_or_insert_with(|| vec![vec![None; dimension]; dimension]);
+        for (row, col, tile) in robot_map_update.changed_tiles {
+            let old_tile = robot_map[row][col].replace(tile.clone());
+            self.world_history.record_robot_tile_change(col, row, old_tile, Some(tile));
+        }
+        self.heatmaps.invalidate_heat_values();
+    }
+
+    #[inline]
+    fn process_plan_record(&mut self, plan: RobotPlan) {
+        println_d!("VISUALIZER: received robot plan {:?}", plan);
+        self.world_state.robot_plan = plan;
+    }
+
+    #[inline]
+    fn process_interface_invocation_record(&mut self, interface_invocation: InterfaceInvocation) {
+        println_d!("VISULAZER: received interface invocation: {:?}", interface_invocation);
+
+        // history cache, actions inferred from the events since the last invocation come first, failed call leaves no events to explain
+        let position = interface_invocation.robot_position();
+        if !interface_invocation.during_event_handling {
+            if interface_invocation.outcome.succeeded() {
+                self.action_inference.explain(&interface_invocation.interface_action, position, &interface_invocation.outcome);
+            }
+            self.add_inferred_actions_to_history();
+        }
+        self.action_history.add_record(interface_invocation.interface_action, position, Some(interface_invocation.outcome));
+
+        //rizzler
+        if let Some(meesage) = interface_invocation.riz_message {
+            self.world_state.rizzler_messages.push(meesage);
+        }
+    }
+
+    fn add_inferred_actions_to_history(&mut self) {
+        for (action, robot_position) in self.action_inference.take_pending_actions() {
+            self.action_history.add_record(action, robot_position, None);
+        }
+    }
+
+    fn check_breakpoints(&self, channel_item: &ChannelItem, breakpoints: &mut Breakpoints) -> Option<String> {
+        match channel_item {
+            ChannelItem::EventChannelItem(event) => {
+                let energy_before = self.world_state.robot_energy;
+                let energy_after = match event {
+                    RobotEvent::EnergyConsumed(amount) => energy_before.saturating_sub(*amount),
+                    _ => energy_before
+                };
+                breakpoints.check_event(event, energy_before, energy_after)
+            }
+            ChannelItem::InterfaceChannelItem(interface_invocation) => breakpoints.check_action(&interface_invocation.interface_action),
+            _ => None
+        }
+    }
+
+    /// Processes one item received from the robot, when there is none the tick is finished.
+    /// Returns description of the breakpoint hit by the item, if any.
+    pub(super) fn process_robotic_lib_event(&mut self, tick_counter: usize, breakpoints: &mut Breakpoints) -> Result<Option<String>, OhCrabVisualizerError> {
+                let received_state = self.robot_receiver.try_recv();
+                let mut breakpoint_hit = None;
+
+                match received_state {
+                    Ok(channel_item) => {
+                        self.record_item(tick_counter, &channel_item)?;
+                        breakpoint_hit = self.check_breakpoints(&channel_item, breakpoints);
+                        match  channel_item {
+                            ChannelItem::EventChannelItem(event) => {
+                                self.action_inference.process_event(&event);
+                                match event {
+                                    // RobotEvent::Ready => todo!(),
+                                    // RobotEvent::Terminated => todo!(),
+                                    RobotEvent::TimeChanged(env_conditions) => {
+                                        self.process_time_changed_event(env_conditions);
+                                    }
+                                    RobotEvent::DayChanged(env_conditions) => {
+                                        self.process_day_changed_event(env_conditions);
+                                    }
+                                    RobotEvent::EnergyRecharged(amount) => {
+                                        self.process_energy_regarged_event(amount);
+                                    },
+                                    RobotEvent::EnergyConsumed(amount) => {
+                                        self.process_energy_consumed_event(amount);
+                                    },
+                                    RobotEvent::Moved(_, (robot_y, robot_x)) => { // BEWARE: library has x and y switched in Move event
+                                        self.process_moved_event(robot_x, robot_y);
+                                    }
+                                    RobotEvent::TileContentUpdated(tile, (tile_y, tile_x)) => {
+                                        self.process_tile_content_update_event(tile, tile_x, tile_y);
+                                    }
+                                    RobotEvent::AddedToBackpack(content, amount) => {
+                                        self.process_added_to_backpack_event(content, amount);
+                                    }
+                                    RobotEvent::RemovedFromBackpack(content, amount) => {
+                                        self.process_removed_from_backpack_event(content, amount);
+                                    }
+                                    _ => {
+                                        println_d!("VISUALIZER: {:?}", event);
+                                    }
+                                }
+                            }
+                            ChannelItem::InterfaceChannelItem(interface_invocation) => {
+                                self.process_interface_invocation_record(interface_invocation);
+                            }
+                            ChannelItem::RobotMapChannelItem(robot_map_update) => {
+                                self.process_robot_map_record(robot_map_update);
+                            }
+                            ChannelItem::ScoreChannelItem(score) => {
+                                self.world_state.score = score;
+                            }
+                            ChannelItem::PlanChannelItem(plan) => {
+                                self.process_plan_record(plan);
+                            }
+                            ChannelItem::AnnotationChannelItem(_) => {} // annotations are only logged and recorded
+                            ChannelItem::TickEndedChannelItem => {}     // end of the tick is recognized by the empty channel
+                        }
+                    }
+                    Err(mpsc::TryRecvError::Empty) => {
+                        self.add_inferred_actions_to_history();
+                        self.world_tick_in_progress = false;
+                    }
+                    Err(error) => {
+                        println_d!("VISUALIZER: try receive error: {:?}.", error);
+                        return Err(OhCrabVisualizerError::DataError(DataChannelError::TryRecvError(error)));
+                    }
+                }
+                Ok(breakpoint_hit.map(|description| format!("{}: {}", self.name, description)))
+    }
+}
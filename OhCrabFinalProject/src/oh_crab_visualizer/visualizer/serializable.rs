@@ -0,0 +1,285 @@
+use robotics_lib::{event::events::Event as RobotEvent, interface::Direction, world::{tile::{Tile, Content}, environmental_conditions::EnvironmentalConditions}};
+use rstykrab_cache::Action;
+use serde::{Serialize, Deserialize};
+
+use super::{visualizer_event_listener::{ChannelItem, InterfaceInvocation, InterfaceOutcome, RobotPlan, RobotMapUpdate}, visualizable_robot::InitWorldState, Coord};
+
+// Serializable mirrors of the types that travel through the visualizer channels.
+// `Event` from robotic-lib and `Action` from history cache cannot be serialized directly.
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) enum SerializableDirection {
+    Up,
+    Down,
+    Left,
+    Right
+}
+
+impl From<&Direction> for SerializableDirection {
+    fn from(direction: &Direction) -> Self {
+        match direction {
+            Direction::Up => SerializableDirection::Up,
+            Direction::Down => SerializableDirection::Down,
+            Direction::Left => SerializableDirection::Left,
+            Direction::Right => SerializableDirection::Right,
+        }
+    }
+}
+
+impl From<SerializableDirection> for Direction {
+    fn from(direction: SerializableDirection) -> Self {
+        match direction {
+            SerializableDirection::Up => Direction::Up,
+            SerializableDirection::Down => Direction::Down,
+            SerializableDirection::Left => Direction::Left,
+            SerializableDirection::Right => Direction::Right,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) enum SerializableEvent {
+    Ready,
+    Terminated,
+    TimeChanged(EnvironmentalConditions),
+    DayChanged(EnvironmentalConditions),
+    EnergyRecharged(usize),
+    EnergyConsumed(usize),
+    Moved(Tile, (usize, usize)),
+    TileContentUpdated(Tile, (usize, usize)),
+    AddedToBackpack(Content, usize),
+    RemovedFromBackpack(Content, usize)
+}
+
+impl From<&RobotEvent> for SerializableEvent {
+    fn from(event: &RobotEvent) -> Self {
+        match event {
+            RobotEvent::Ready => SerializableEvent::Ready,
+            RobotEvent::Terminated => SerializableEvent::Terminated,
+            RobotEvent::TimeChanged(env_conditions) => SerializableEvent::TimeChanged(env_conditions.clone()),
+            RobotEvent::DayChanged(env_conditions) => SerializableEvent::DayChanged(env_conditions.clone()),
+            RobotEvent::EnergyRecharged(amount) => SerializableEvent::EnergyRecharged(*amount),
+            RobotEvent::EnergyConsumed(amount) => SerializableEvent::EnergyConsumed(*amount),
+            RobotEvent::Moved(tile, position) => SerializableEvent::Moved(tile.clone(), *position),
+            RobotEvent::TileContentUpdated(tile, position) => SerializableEvent::TileContentUpdated(tile.clone(), *position),
+            RobotEvent::AddedToBackpack(content, amount) => SerializableEvent::AddedToBackpack(content.clone(), *amount),
+            RobotEvent::RemovedFromBackpack(content, amount) => SerializableEvent::RemovedFromBackpack(content.clone(), *amount),
+        }
+    }
+}
+
+impl From<SerializableEvent> for RobotEvent {
+    fn from(event: SerializableEvent) -> Self {
+        match event {
+            SerializableEvent::Ready => RobotEvent::Ready,
+            SerializableEvent::Terminated => RobotEvent::Terminated,
+            SerializableEvent::TimeChanged(env_conditions) => RobotEvent::TimeChanged(env_conditions),
+            SerializableEvent::DayChanged(env_conditions) => RobotEvent::DayChanged(env_conditions),
+            SerializableEvent::EnergyRecharged(amount) => RobotEvent::EnergyRecharged(amount),
+            SerializableEvent::EnergyConsumed(amount) => RobotEvent::EnergyConsumed(amount),
+            SerializableEvent::Moved(tile, position) => RobotEvent::Moved(tile, position),
+            SerializableEvent::TileContentUpdated(tile, position) => RobotEvent::TileContentUpdated(tile, position),
+            SerializableEvent::AddedToBackpack(content, amount) => RobotEvent::AddedToBackpack(content, amount),
+            SerializableEvent::RemovedFromBackpack(content, amount) => RobotEvent::RemovedFromBackpack(content, amount),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) enum SerializableAction {
+    Craft(Content),
+    Destroy(SerializableDirection),
+    DiscoverTiles(Vec<(usize, usize)>),
+    GetScore,
+    Go(SerializableDirection),
+    LookAtSky,
+    OneDirectionView(SerializableDirection, usize),
+    Put(Content, usize, SerializableDirection),
+    RobotMap,
+    RobotView,
+    Teleport((usize, usize)),
+    WhereAmI,
+    Other(String)
+}
+
+impl From<&Action> for SerializableAction {
+    fn from(action: &Action) -> Self {
+        match action {
+            Action::Craft(content) => SerializableAction::Craft(content.clone()),
+            Action::Destroy(direction) => SerializableAction::Destroy(direction.into()),
+            Action::DiscoverTiles(tiles) => SerializableAction::DiscoverTiles(tiles.clone()),
+            Action::GetScore() => SerializableAction::GetScore,
+            Action::Go(direction) => SerializableAction::Go(direction.into()),
+            Action::LookAtSky() => SerializableAction::LookAtSky,
+            Action::OneDirectionView(direction, distance) => SerializableAction::OneDirectionView(direction.into(), *distance),
+            Action::Put(content, amount, direction) => SerializableAction::Put(content.clone(), *amount, direction.into()),
+            Action::RobotMap() => SerializableAction::RobotMap,
+            Action::RobotView() => SerializableAction::RobotView,
+            Action::Teleport(coordinates) => SerializableAction::Teleport(*coordinates),
+            Action::WhereAmI() => SerializableAction::WhereAmI,
+            Action::Other(label) => SerializableAction::Other(label.to_string()),
+        }
+    }
+}
+
+impl From<SerializableAction> for Action {
+    fn from(action: SerializableAction) -> Self {
+        match action {
+            SerializableAction::Craft(content) => Action::Craft(content),
+            SerializableAction::Destroy(direction) => Action::Destroy(direction.into()),
+            SerializableAction::DiscoverTiles(tiles) => Action::DiscoverTiles(tiles),
+            SerializableAction::GetScore => Action::GetScore(),
+            SerializableAction::Go(direction) => Action::Go(direction.into()),
+            SerializableAction::LookAtSky => Action::LookAtSky(),
+            SerializableAction::OneDirectionView(direction, distance) => Action::OneDirectionView(direction.into(), distance),
+            SerializableAction::Put(content, amount, direction) => Action::Put(content, amount, direction.into()),
+            SerializableAction::RobotMap => Action::RobotMap(),
+            SerializableAction::RobotView => Action::RobotView(),
+            SerializableAction::Teleport(coordinates) => Action::Teleport(coordinates),
+            SerializableAction::WhereAmI => Action::WhereAmI(),
+            SerializableAction::Other(label) => Action::Other(label),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct SerializableInterfaceInvocation {
+    pub(super) action: SerializableAction,
+    pub(super) robot_position: (usize, usize),  // (x, y)
+    pub(super) riz_message: Option<String>,
+    #[serde(default)]
+    pub(super) outcome: InterfaceOutcome,   // missing in recordings made before outcomes were recorded
+    #[serde(default)]
+    pub(super) during_event_handling: bool
+}
+
+impl From<&InterfaceInvocation> for SerializableInterfaceInvocation {
+    fn from(invocation: &InterfaceInvocation) -> Self {
+        SerializableInterfaceInvocation {
+            action: (&invocation.interface_action).into(),
+            robot_position: (invocation.robot_position.x, invocation.robot_position.y),
+            riz_message: invocation.riz_message.clone(),
+            outcome: invocation.outcome.clone(),
+            during_event_handling: invocation.during_event_handling
+        }
+    }
+}
+
+impl From<SerializableInterfaceInvocation> for InterfaceInvocation {
+    fn from(invocation: SerializableInterfaceInvocation) -> Self {
+        let (x, y) = invocation.robot_position;
+        let mut interface_invocation = InterfaceInvocation::new(invocation.action.into(), Coord::new(x, y), invocation.riz_message, invocation.outcome);
+        interface_invocation.during_event_handling = invocation.during_event_handling;
+        interface_invocation
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct SerializablePlan {
+    pub(super) path: Vec<(usize, usize)>,   // (x, y)
+    pub(super) target: Option<(usize, usize)>
+}
+
+impl From<&RobotPlan> for SerializablePlan {
+    fn from(plan: &RobotPlan) -> Self {
+        SerializablePlan {
+            path: plan.path.iter().map(|coord| (coord.x, coord.y)).collect(),
+            target: plan.target.as_ref().map(|coord| (coord.x, coord.y))
+        }
+    }
+}
+
+impl From<SerializablePlan> for RobotPlan {
+    fn from(plan: SerializablePlan) -> Self {
+        RobotPlan {
+            path: plan.path.into_iter().map(|(x, y)| Coord::new(x, y)).collect(),
+            target: plan.target.map(|(x, y)| Coord::new(x, y))
+        }
+    }
+}
+
+/// Only tiles of the robot map that changed during the tick are stored, the same way as they are published
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct SerializableRobotMapUpdate {
+    pub(super) world_dimension: usize,
+    pub(super) changed_tiles: Vec<(usize, usize, Tile)> // (x, y, tile)
+}
+
+impl From<&RobotMapUpdate> for SerializableRobotMapUpdate {
+    fn from(update: &RobotMapUpdate) -> Self {
+        SerializableRobotMapUpdate {
+            world_dimension: update.world_dimension,
+            changed_tiles: update.changed_tiles.iter().map(|(row, col, tile)| (*col, *row, tile.clone())).collect()
+        }
+    }
+}
+
+impl From<SerializableRobotMapUpdate> for RobotMapUpdate {
+    fn from(update: SerializableRobotMapUpdate) -> Self {
+        RobotMapUpdate {
+            world_dimension: update.world_dimension,
+            changed_tiles: update.changed_tiles.into_iter().map(|(x, y, tile)| (y, x, tile)).collect()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) enum SerializableChannelItem {
+    Event(SerializableEvent),
+    InterfaceInvocation(SerializableInterfaceInvocation),
+    RobotMapUpdate(SerializableRobotMapUpdate),
+    Score(f32),
+    Plan(SerializablePlan),
+    Annotation(String),
+    TickEnded
+}
+
+impl SerializableChannelItem {
+    pub(super) fn from_channel_item(item: &ChannelItem) -> SerializableChannelItem {
+        match item {
+            ChannelItem::EventChannelItem(event) => SerializableChannelItem::Event(event.into()),
+            ChannelItem::InterfaceChannelItem(invocation) => SerializableChannelItem::InterfaceInvocation(invocation.into()),
+            ChannelItem::RobotMapChannelItem(update) => SerializableChannelItem::RobotMapUpdate(update.into()),
+            ChannelItem::ScoreChannelItem(score) => SerializableChannelItem::Score(*score),
+            ChannelItem::PlanChannelItem(plan) => SerializableChannelItem::Plan(plan.into()),
+            ChannelItem::AnnotationChannelItem(annotation) => SerializableChannelItem::Annotation(annotation.clone()),
+            ChannelItem::TickEndedChannelItem => SerializableChannelItem::TickEnded,
+        }
+    }
+
+    pub(super) fn into_channel_item(self) -> ChannelItem {
+        match self {
+            SerializableChannelItem::Event(event) => ChannelItem::EventChannelItem(event.into()),
+            SerializableChannelItem::InterfaceInvocation(invocation) => ChannelItem::InterfaceChannelItem(invocation.into()),
+            SerializableChannelItem::RobotMapUpdate(update) => ChannelItem::RobotMapChannelItem(update.into()),
+            SerializableChannelItem::Score(score) => ChannelItem::ScoreChannelItem(score),
+            SerializableChannelItem::Plan(plan) => ChannelItem::PlanChannelItem(plan.into()),
+            SerializableChannelItem::Annotation(annotation) => ChannelItem::AnnotationChannelItem(annotation),
+            SerializableChannelItem::TickEnded => ChannelItem::TickEndedChannelItem,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct SerializableInitState {
+    pub(super) world_map: Vec<Vec<Tile>>,
+    pub(super) robot_position: (usize, usize),
+    pub(super) robot_energy: usize
+}
+
+impl From<&InitWorldState> for SerializableInitState {
+    fn from(state: &InitWorldState) -> Self {
+        SerializableInitState {
+            world_map: state.world_map.clone(),
+            robot_position: (state.robot_position.x, state.robot_position.y),
+            robot_energy: state.robot_energy
+        }
+    }
+}
+
+impl From<SerializableInitState> for InitWorldState {
+    fn from(state: SerializableInitState) -> Self {
+        InitWorldState::new(state.world_map, state.robot_position, state.robot_energy)
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::visualizer::{OhCrabVisualizerError, RunMode};
+
+/// Visualizer settings loadable from TOML or JSON file, every missing value falls back to its default.
+/// Settings are used via `OhCrabVisualizerConfig::from_settings_file` or `OhCrabVisualizerConfig::with_settings`.
+///
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct VisualizerSettings {
+    pub run_mode: RunModeSettings,
+    pub use_sound: bool,
+    pub history_cache_length: usize,    // number of actions shown in the history cache window
+    pub max_energy_level: usize,        // full energy bar
+    pub layout: LayoutSettings,
+    pub tiles: TileSettings,
+    pub panels: PanelSettings,
+    pub assets: AssetSettings,
+    pub sound: SoundSettings
+}
+
+/// Run mode used when the config is created from the settings file, e.g. `run_mode = "interactive"`
+/// or `run_mode = { non_interactive = 500 }` in TOML
+///
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RunModeSettings {
+    Interactive,
+    NonInteractive(usize)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LayoutSettings {
+    pub window_maximized: bool,
+    pub window_width: f32,          // used only when the window is not maximized
+    pub window_height: f32,
+    pub side_panel_width: f32,      // space left of the tile grid
+    pub grid_frame_width: f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TileSettings {
+    pub default_tile_size: f32,     // tile size after start and after `Zoom on robot`
+    pub max_tile_size: f32
+}
+
+/// Windows that are displayed, the `Scroll world` window is always displayed
+///
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PanelSettings {
+    pub timeline: bool,
+    pub robots: bool,
+    pub backpack: bool,
+    pub time: bool,
+    pub energy: bool,
+    pub history_cache: bool,
+    pub rizzler: bool,
+    pub tile_inspector: bool,
+    pub minimap: bool,
+    pub charts: bool,
+    pub breakpoints: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AssetSettings {
+    pub resource_path: String,          // directory containing `images`
+    pub audio_path: Option<String>      // directory containing `event`, `tile` and `weather` sounds, `audio` inside `resource_path` when not given
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub event_volume: f32,
+    pub energy_recharged_volume: f32,
+    pub tile_volume: f32,
+    pub weather_volume: f32
+}
+
+impl Default for VisualizerSettings {
+    fn default() -> Self {
+        VisualizerSettings {
+            run_mode: RunModeSettings::Interactive,
+            use_sound: false,
+            history_cache_length: 50,
+            max_energy_level: 1000,
+            layout: LayoutSettings::default(),
+            tiles: TileSettings::default(),
+            panels: PanelSettings::default(),
+            assets: AssetSettings::default(),
+            sound: SoundSettings::default()
+        }
+    }
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        LayoutSettings { window_maximized: true, window_width: 1600.0, window_height: 1000.0, side_panel_width: 200.0, grid_frame_width: 20.0 }
+    }
+}
+
+impl Default for TileSettings {
+    fn default() -> Self {
+        TileSettings { default_tile_size: 60.4, max_tile_size: 120.8 }
+    }
+}
+
+impl Default for PanelSettings {
+    fn default() -> Self {
+        PanelSettings {
+            timeline: true,
+            robots: true,
+            backpack: true,
+            time: true,
+            energy: true,
+            history_cache: true,
+            rizzler: true,
+            tile_inspector: true,
+            minimap: true,
+            charts: true,
+            breakpoints: true
+        }
+    }
+}
+
+impl Default for AssetSettings {
+    fn default() -> Self {
+        AssetSettings { resource_path: "./assets".to_owned(), audio_path: None }
+    }
+}
+
+impl AssetSettings {
+    pub fn audio_path(&self) -> String {
+        match &self.audio_path {
+            Some(audio_path) => audio_path.clone(),
+            None => format!("{}/audio", self.resource_path),
+        }
+    }
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        SoundSettings { event_volume: 1.0, energy_recharged_volume: 0.1, tile_volume: 1.0, weather_volume: 1.0 }
+    }
+}
+
+impl VisualizerSettings {
+    /// Format is given by the file extension, `.toml` or `.json`
+    pub fn load(settings_path: &str) -> Result<VisualizerSettings, OhCrabVisualizerError> {
+        let content = fs::read_to_string(settings_path).map_err(|err| settings_error(settings_path, err))?;
+        match Path::new(settings_path).extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|err| settings_error(settings_path, err)),
+            Some("json") => serde_json::from_str(&content).map_err(|err| settings_error(settings_path, err)),
+            _ => Err(OhCrabVisualizerError::ConfigurationError(format!("Settings file {} has unknown format, expected .toml or .json", settings_path)))
+        }
+    }
+
+    /// Origin of the tile grid on the canvas
+    pub(super) fn grid_canvas_origin(&self) -> (f32, f32) {
+        (self.layout.side_panel_width + self.layout.grid_frame_width, self.layout.grid_frame_width)
+    }
+}
+
+impl From<&RunModeSettings> for RunMode {
+    fn from(run_mode: &RunModeSettings) -> Self {
+        match run_mode {
+            RunModeSettings::Interactive => RunMode::Interactive,
+            RunModeSettings::NonInteractive(total_ticks) => RunMode::NonInteractive(*total_ticks),
+        }
+    }
+}
+
+fn settings_error(settings_path: &str, error: impl std::fmt::Display) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ConfigurationError(format!("Settings file {}: {}", settings_path, error))
+}
@@ -0,0 +1,72 @@
+use std::{fs::{self, File}, io::{BufWriter, Write}, path::Path};
+
+use super::visualizer::OhCrabVisualizerError;
+
+const CSV_HEADER: &str = "tick,energy,energy_consumed,energy_recharged,score,backpack_fill";
+
+/// Values of the robot's world at the end of one tick
+///
+#[derive(Default, Clone, Debug)]
+pub(super) struct TickSample {
+    pub(super) tick: usize,
+    pub(super) energy: usize,
+    pub(super) energy_consumed: usize,
+    pub(super) energy_recharged: usize,
+    pub(super) score: f32,
+    pub(super) backpack_fill: usize     // number of items in the backpack
+}
+
+/// Per tick values of the whole run, displayed in the charts window and exportable to CSV
+///
+#[derive(Default)]
+pub(super) struct TimeSeries {
+    samples: Vec<TickSample>,
+    energy_consumed: usize,     // during the tick in progress
+    energy_recharged: usize
+}
+
+impl TimeSeries {
+    pub(super) fn samples(&self) -> &Vec<TickSample> {
+        &self.samples
+    }
+
+    pub(super) fn record_energy_consumed(&mut self, amount: usize) {
+        self.energy_consumed += amount;
+    }
+
+    pub(super) fn record_energy_recharged(&mut self, amount: usize) {
+        self.energy_recharged += amount;
+    }
+
+    /// Closes the tick, energy consumed and recharged are counted from zero for the next one
+    pub(super) fn commit_tick(&mut self, tick: usize, energy: usize, score: f32, backpack_fill: usize) {
+        self.samples.push(TickSample {
+            tick,
+            energy,
+            energy_consumed: std::mem::take(&mut self.energy_consumed),
+            energy_recharged: std::mem::take(&mut self.energy_recharged),
+            score,
+            backpack_fill
+        });
+    }
+
+    pub(super) fn write_csv(&self, csv_path: &str) -> Result<(), OhCrabVisualizerError> {
+        if let Some(parent) = Path::new(csv_path).parent() {
+            fs::create_dir_all(parent).map_err(|err| csv_error(csv_path, err))?;
+        }
+        let file = File::create(csv_path).map_err(|err| csv_error(csv_path, err))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", CSV_HEADER).map_err(|err| csv_error(csv_path, err))?;
+        for sample in self.samples.iter() {
+            writeln!(writer, "{},{},{},{},{},{}", sample.tick, sample.energy, sample.energy_consumed, sample.energy_recharged, sample.score, sample.backpack_fill)
+                .map_err(|err| csv_error(csv_path, err))?;
+        }
+        writer.flush().map_err(|err| csv_error(csv_path, err))?;
+        println!("Time series exported to {}", csv_path);
+        Ok(())
+    }
+}
+
+fn csv_error(csv_path: &str, error: std::io::Error) -> OhCrabVisualizerError {
+    OhCrabVisualizerError::ReportError(format!("Time series file {}: {}", csv_path, error))
+}
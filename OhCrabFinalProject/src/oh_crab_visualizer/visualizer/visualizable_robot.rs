@@ -1,7 +1,7 @@
 use std::sync::mpsc::Sender;
-use robotics_lib::{runner::Runnable, interface::debug, event::events::Event as RobotEvent, world::tile::Tile};
+use robotics_lib::{runner::Runnable, interface::{debug, robot_map, get_score}, event::events::Event as RobotEvent, world::{tile::Tile, World}};
 use crate::println_d;
-use super::{visualizer_event_listener::VisualizerEventListener, Coord};
+use super::{visualizer_event_listener::{VisualizerEventListener, ChannelItem, RobotMapUpdate}, Coord};
 
 // trait RunnableVisualizable<'a>: Runnable + Visalizable<'a> {
 // }
@@ -10,8 +10,100 @@ pub trait Visulizable<'a> {
     fn borrow_event_listener(&'a self) -> &'a VisualizerEventListener;
 }
 
+/// Implements `Visulizable` for a robot that keeps its `VisualizerEventListener` in the given field,
+/// e.g. `visulizable!(ExampleRobot, visualizer_event_listener);`
+#[macro_export]
+macro_rules! visulizable {
+    ($robot:ty, $listener_field:ident) => {
+        impl<'a> $crate::oh_crab_visualizer::visualizer::visualizable_robot::Visulizable<'a> for $robot {
+            fn borrow_event_listener(&'a self) -> &'a $crate::oh_crab_visualizer::visualizer::visualizer_event_listener::VisualizerEventListener {
+                &self.$listener_field
+            }
+        }
+    };
+}
+
 pub trait RobotCreator {
     fn create(&self, event_listener: VisualizerEventListener) -> Box<dyn Runnable>;
+
+    /// Creates the robot wrapped in `EventForwardingRobot`, every event sent by the runner is published without the robot's help
+    fn create_forwarding(&self, event_listener: VisualizerEventListener) -> Box<dyn Runnable> {
+        Box::new(EventForwardingRobot::new(self.create(event_listener.clone()), event_listener))
+    }
+}
+
+/// Allows to pass robot creator chosen at runtime, e.g. from command line
+impl<T: RobotCreator + ?Sized> RobotCreator for Box<T> {
+    fn create(&self, event_listener: VisualizerEventListener) -> Box<dyn Runnable> {
+        self.as_ref().create(event_listener)
+    }
+}
+
+/// Publishes every event sent by the runner to the listener before the robot handles it.
+/// After every tick of the robot the tiles of its known map that changed and the world score are published and the end of the tick
+/// is marked by `TickEndedChannelItem`, so that sinks can tell the ticks apart even when the robot runs without the visualizer.
+///
+pub struct EventForwardingRobot {
+    real_robot: Box<dyn Runnable>,
+    event_listener: VisualizerEventListener,
+    last_robot_map: Option<Vec<Vec<Option<Tile>>>>  // map the last update was computed from
+}
+
+impl EventForwardingRobot {
+    /// `event_listener` has to be a clone of the listener the robot was created with
+    pub fn new(real_robot: Box<dyn Runnable>, event_listener: VisualizerEventListener) -> EventForwardingRobot {
+        EventForwardingRobot { real_robot, event_listener, last_robot_map: None }
+    }
+
+    /// Nothing is published when robot has not discovered anything new
+    fn publish_robot_map(&mut self, world: &World) {
+        if let Some(known_map) = robot_map(world) {
+            let update = RobotMapUpdate::from_maps(&self.last_robot_map, &known_map);
+            self.last_robot_map = Some(known_map);
+            if !update.is_empty() {
+                self.event_listener.publish(ChannelItem::RobotMapChannelItem(update));
+            }
+        }
+    }
+}
+
+impl Runnable for EventForwardingRobot {
+    fn process_tick(&mut self, world: &mut robotics_lib::world::World) {
+        self.real_robot.process_tick(world);
+        self.publish_robot_map(world);
+        self.event_listener.publish(ChannelItem::ScoreChannelItem(get_score(world)));
+        self.event_listener.publish(ChannelItem::TickEndedChannelItem);
+    }
+
+    fn handle_event(&mut self, event: RobotEvent) {
+        self.event_listener.begin_forwarded_event(&event);
+        self.real_robot.handle_event(event);
+        self.event_listener.end_forwarded_event();
+    }
+
+    fn get_energy(&self) -> &robotics_lib::energy::Energy {
+        self.real_robot.get_energy()
+    }
+
+    fn get_energy_mut(&mut self) -> &mut robotics_lib::energy::Energy {
+        self.real_robot.get_energy_mut()
+    }
+
+    fn get_coordinate(&self) -> &robotics_lib::world::coordinates::Coordinate {
+        self.real_robot.get_coordinate()
+    }
+
+    fn get_coordinate_mut(&mut self) -> &mut robotics_lib::world::coordinates::Coordinate {
+        self.real_robot.get_coordinate_mut()
+    }
+
+    fn get_backpack(&self) -> &robotics_lib::runner::backpack::BackPack {
+        self.real_robot.get_backpack()
+    }
+
+    fn get_backpack_mut(&mut self) -> &mut robotics_lib::runner::backpack::BackPack {
+        self.real_robot.get_backpack_mut()
+    }
 }
 
 pub(super) struct VisualizableRobot {
@@ -21,6 +113,7 @@ pub(super) struct VisualizableRobot {
 }
 
 impl VisualizableRobot {
+    /// `real_robot` is expected to be wrapped in `EventForwardingRobot`, which publishes everything else the visualizer needs
     pub(super) fn new(real_robot: Box<dyn Runnable>, map_sender: Sender<InitStateChannelItem>) -> VisualizableRobot {
         VisualizableRobot {
             real_robot: real_robot,
@@ -33,7 +126,7 @@ impl VisualizableRobot {
 impl Runnable for VisualizableRobot {
     fn process_tick(&mut self, world: &mut robotics_lib::world::World) {
         self.init_state(world);
-        self.real_robot.process_tick(world)
+        self.real_robot.process_tick(world);
     }
 
     fn handle_event(&mut self, event: RobotEvent) {
@@ -73,7 +166,7 @@ impl VisualizableRobot {
             //println_d!("{:?}", map);
             let robot_energy = self.get_energy();
             let energy_amount = robot_energy.get_energy_level();
-            println!("VISUALIZABLE ROBOT: energy {}", energy_amount); //TODO debgu print
+            println_d!("VISUALIZABLE ROBOT: energy {}", energy_amount);
             let world_state = InitWorldState::new(map, (robot_x, robot_y), energy_amount);
             self.map_sender.send(InitStateChannelItem { state: world_state }).expect("Sending state from robot to visualizer failed");
             self.is_initialized = true
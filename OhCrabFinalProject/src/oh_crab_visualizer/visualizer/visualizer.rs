@@ -1,89 +1,54 @@
-use std::{sync::mpsc::{Receiver, self}, collections::HashMap};
-
 use egui::Visuals;
 use egui_extras::install_image_loaders;
 use ggegui::{egui::{self}, Gui, GuiContext};
-use ggez::{event::{EventHandler, self}, graphics::{self, DrawParam}, GameError, glam};
+use ggez::{event::{EventHandler, MouseButton, self}, graphics::{self, DrawParam, Color}, input::keyboard::{KeyCode, KeyInput}, GameError, glam};
 use oxagworldgenerator::world_generator::OxAgWorldGenerator;
-use rand::{rngs::ThreadRng, seq::SliceRandom};
-use robotics_lib::{runner::Runner, utils::LibError as RobotError, event::events::Event as RobotEvent, world::{tile::{Tile, Content}, environmental_conditions::{WeatherType, EnvironmentalConditions}}};
-use rstykrab_cache::Cache;
+use rand::rngs::ThreadRng;
+use robotics_lib::{utils::LibError as RobotError, world::environmental_conditions::{WeatherType, EnvironmentalConditions}};
 
-use crate::{oh_crab_visualizer::visualizer::{draw_utils::{self, GridCanvasProperties}, egui_utils}, println_d};
+use crate::{oh_crab_visualizer::visualizer::{draw_utils::{self, GridCanvasProperties, RobotMarker}, egui_utils}, println_d};
 
-use super::{visualizable_robot::{VisualizableRobot, RobotCreator, InitStateChannelItem}, Coord, visualizer_event_listener::{VisualizerEventListener, ChannelItem, InterfaceInvocation}, egui_utils::EguiImages, draw_utils::GgezImages};
+use super::{visualizable_robot::RobotCreator, Coord, egui_utils::{EguiImages, Minimap}, draw_utils::GgezImages, robot_session::{RobotSession, ROBOT_COLORS}, playback::{Playback, MIN_TICKS_PER_SECOND, MAX_TICKS_PER_SECOND}, breakpoints::{Breakpoint, Breakpoints}, heatmap::heatmap_contents, frame_export::{FrameExportConfig, FrameExporter}, settings::VisualizerSettings, event_sink::{EventSink, EventSinkFactory, JsonLinesSink}, live_stream::LiveStream};
 
 //pub(super) const TILE_SIZE_MIN:f32 = 5.0;
-pub(super) const TILE_SIZE_MAX:f32 = 120.8;
 pub(super) const CONTENT_TILE_SIZE_LIMIT:f32 = 50.0;
 
-pub(super) const DEFAULT_TILE_SIZE:f32 = 60.4;
-pub(super) const GRID_FRAME_WIDTH: f32 = 20.0;
-pub(super) const GRID_CANVAS_ORIGIN_X: f32 = 200.0 + GRID_FRAME_WIDTH;
-pub(super) const GRID_CANVAS_ORIGIN_Y: f32 = 0.0 + GRID_FRAME_WIDTH;
-
-pub(super) const MAX_ENERGY_LEVEL: usize = 1000;
+const CAMERA_PAN_STEP: f32 = 3.0;   // tiles moved by one key press
+const ZOOM_STEP: f32 = 1.1;         // tile size multiplier of one wheel notch or key press
+const DRAG_THRESHOLD: f32 = 4.0;    // pixels the mouse has to move before the click becomes a drag
 
 pub struct OhCrabVisualizer {
-    runner: Runner,
-    robot_receiver: Receiver<ChannelItem>,
-    map_receiver: Receiver<InitStateChannelItem>,
-    action_cache: Cache,
+    robots: Vec<RobotSession>,
+    selected_robot: usize,
     rng: ThreadRng,
     
     gui: Gui,
     egui_images: EguiImages<'static>,
     ggez_images: GgezImages,
+    minimap: Minimap,
 
     // configuration
     run_mode: RunMode,
 
     // state
     tick_counter: usize,
-    world_state: WorldState,
-    world_time: WorldTime,
-    world_tick_in_progress: bool,
+    playback: Playback,
+    breakpoints: Breakpoints,
     visualization_state: VisualizationState
 }
 
-/// Represents state of robotic lib world as it is known to visualizer
-/// 
-struct WorldState {
-    world_map: Option<Vec<Vec<Tile>>>,
-    robot_position: Option<Coord>,
-    backpack: HashMap<Content, usize>,
-    robot_energy: usize,
-    previous_tick_energy_difference: i32,
-    current_tick_energy_difference: i32,
-    rizler_message: Option<String>,
-    rizzler_messages: Vec<String>
-}
-
-impl WorldState {
-    fn empty() -> WorldState {
-        WorldState {
-            world_map: None,
-            robot_position: None,
-            backpack: HashMap::new(),
-            robot_energy: 0,
-            current_tick_energy_difference: 0,
-            previous_tick_energy_difference: 0,
-            rizler_message: None,
-            rizzler_messages: Vec::new()
-        }
-    }
-}
-
+#[derive(Clone)]
 pub(super) struct WorldTime {
     pub(super) day_counter: u64,
     pub(super) hours: u8,
     pub(super) minutes: u8,
-    pub(super) weather: WeatherType
+    pub(super) weather: WeatherType,
+    pub(super) env_conditions: Option<EnvironmentalConditions>  // last received, needed to compute walk costs
 }
 
 impl Default for WorldTime {
     fn default() -> Self {
-        Self { day_counter: Default::default(), hours: Default::default(), minutes: Default::default(), weather: WeatherType::Sunny }
+        Self { day_counter: Default::default(), hours: Default::default(), minutes: Default::default(), weather: WeatherType::Sunny, env_conditions: None }
     }
 }
 
@@ -93,6 +58,7 @@ impl WorldTime {
         self.hours = hours;
         self.minutes = minutes;
         self.weather = env_conds.get_weather_condition();
+        self.env_conditions = Some(env_conds.clone());
     }
 
     fn parse_time(time_str: &str) -> (u8, u8) {
@@ -116,7 +82,22 @@ pub(super) struct VisualizationState {
     offset_x: f32,
     offset_y: f32,
     should_focus_on_robot: bool,
+    jump_to_tick: usize,
+    pub(super) show_robot_paths: bool,
+    pub(super) hovered_tile: Option<Coord>,
+    pub(super) pinned_tile: Option<Coord>,     // tile clicked by user, inspected instead of the hovered one
+    drag_start: Option<(f32, f32)>,             // canvas position where the left button was pressed
+    is_dragging: bool,
+    pub(super) chart_display_option: ChartDisplayOptions,
+    pub(super) chart_export_message: Option<String>,
+    pub(super) history_filter_option: HistoryFilterOptions,
     pub(super) content_display_option: ContentDisplayOptions,
+    pub(super) map_display_option: MapDisplayOptions,
+    pub(super) heatmap_display_option: HeatmapDisplayOptions,
+    pub(super) heatmap_content: usize,          // index to `heatmap_contents`
+    pub(super) tile_shading_option: TileShadingOptions,
+    pub(super) show_time_tint: bool,
+    pub(super) settings: VisualizerSettings,
     pub(super) grid_canvas_properties: GridCanvasProperties
 }
 
@@ -146,6 +127,63 @@ impl VisualizationState {
             && robot_position.y < self.get_last_row_to_display() 
     }
 
+    /// Tile of the world displayed on the given canvas position, None when the position is outside of the tile grid
+    pub(super) fn tile_at_canvas_position(&self, x: f32, y: f32) -> Option<Coord> {
+        let tile_size = self.grid_canvas_properties.tile_size;
+        let grid_x = x - self.grid_canvas_properties.grid_canvas_origin_x;
+        let grid_y = y - self.grid_canvas_properties.grid_canvas_origin_y;
+        if grid_x < 0.0 || grid_y < 0.0 || tile_size <= 0.0 {
+            return None;
+        }
+        let tile = Coord::new(self.first_column_to_display() + (grid_x / tile_size) as usize, self.first_row_to_display() + (grid_y / tile_size) as usize);
+        if self.robot_should_be_displaied(&tile) { Some(tile) } else { None }
+    }
+
+    pub(super) fn inspected_tile(&self) -> Option<&Coord> {
+        self.pinned_tile.as_ref().or(self.hovered_tile.as_ref())
+    }
+
+    /// Moves the camera so that the given tile is in the middle of the grid, camera stays within the world
+    pub(super) fn center_on(&mut self, tile: &Coord) {
+        let half_of_columns_to_display = (self.grid_canvas_properties.num_columns_to_display() / 2) as f32;
+        let half_of_rows_to_display = (self.grid_canvas_properties.num_rows_to_display() / 2) as f32;
+        self.offset_x = tile.x as f32 - half_of_columns_to_display;
+        self.offset_y = tile.y as f32 - half_of_rows_to_display;
+        self.clamp_offset();
+    }
+
+    /// Moves the camera by given number of tiles
+    pub(super) fn pan(&mut self, tiles_x: f32, tiles_y: f32) {
+        self.offset_x += tiles_x;
+        self.offset_y += tiles_y;
+        self.clamp_offset();
+    }
+
+    /// Multiplies tile size by `factor`, the tile under the given canvas position stays under it
+    pub(super) fn zoom_at(&mut self, factor: f32, x: f32, y: f32) {
+        let old_tile_size = self.grid_canvas_properties.tile_size;
+        let new_tile_size = f32::clamp(old_tile_size * factor, self.grid_canvas_properties.tile_size_min, self.settings.tiles.max_tile_size);
+        let grid_x = f32::clamp(x - self.grid_canvas_properties.grid_canvas_origin_x, 0.0, self.grid_canvas_properties.grid_canvas_width);
+        let grid_y = f32::clamp(y - self.grid_canvas_properties.grid_canvas_origin_y, 0.0, self.grid_canvas_properties.grid_canvas_height);
+        self.offset_x += grid_x / old_tile_size - grid_x / new_tile_size;
+        self.offset_y += grid_y / old_tile_size - grid_y / new_tile_size;
+        self.grid_canvas_properties.tile_size = new_tile_size;
+        self.clamp_offset();
+    }
+
+    /// Zooms at the middle of the grid
+    pub(super) fn zoom(&mut self, factor: f32) {
+        let center_x = self.grid_canvas_properties.grid_canvas_origin_x + self.grid_canvas_properties.grid_canvas_width / 2.0;
+        let center_y = self.grid_canvas_properties.grid_canvas_origin_y + self.grid_canvas_properties.grid_canvas_height / 2.0;
+        self.zoom_at(factor, center_x, center_y);
+    }
+
+    fn clamp_offset(&mut self) {
+        let (scroll_limit_x, scroll_limit_y) = self.get_scroll_limit(self.grid_canvas_properties.world_dimension);
+        self.offset_x = f32::clamp(self.offset_x, 0.0, scroll_limit_x);
+        self.offset_y = f32::clamp(self.offset_y, 0.0, scroll_limit_y);
+    }
+
     fn get_scroll_limit(&self, world_dimenstion: usize) -> (f32, f32) {
         let scroll_limit_x = (world_dimenstion - usize::min(world_dimenstion, self.grid_canvas_properties.num_columns_to_display())) as f32;
         let scroll_limit_y = (world_dimenstion - usize::min(world_dimenstion, self.grid_canvas_properties.num_rows_to_display())) as f32;
@@ -158,7 +196,10 @@ pub enum OhCrabVisualizerError {
     RobotLibError(RobotError),
     DataError(DataChannelError),
     GraphicsLibraryError(GameError),
-    ConfigurationError(String)
+    ConfigurationError(String),
+    RecordingError(String),
+    ReportError(String),
+    ExportError(String)
 }
 
 #[derive(Debug)]
@@ -175,6 +216,12 @@ pub enum RunMode {
 pub struct OhCrabVisualizerConfig {
     run_mode: RunMode,
     use_sound: bool,
+    recording_path: Option<String>,
+    event_log_path: Option<String>,
+    breakpoints: Vec<Breakpoint>,
+    settings: VisualizerSettings,
+    event_sink_factories: Vec<EventSinkFactory>,
+    live_stream_address: Option<String>,
 }
 
 impl OhCrabVisualizerConfig {
@@ -182,8 +229,65 @@ impl OhCrabVisualizerConfig {
         OhCrabVisualizerConfig {
             run_mode,
             use_sound,
+            recording_path: None,
+            event_log_path: None,
+            breakpoints: Vec::new(),
+            settings: VisualizerSettings::default(),
+            event_sink_factories: Vec::new(),
+            live_stream_address: None,
         }
     }
+
+    /// Config with run mode, sound and all the other settings loaded from TOML or JSON file, see `VisualizerSettings`
+    pub fn from_settings_file(settings_path: &str) -> Result<Self, OhCrabVisualizerError> {
+        let settings = VisualizerSettings::load(settings_path)?;
+        Ok(OhCrabVisualizerConfig::new(RunMode::from(&settings.run_mode), settings.use_sound).with_settings(settings))
+    }
+
+    /// Layout, tile sizes, displayed windows, asset paths and sound volumes, run mode and sound given in the settings are ignored
+    pub fn with_settings(mut self, settings: VisualizerSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Visualization is paused when the breakpoint is hit, breakpoints can be also added and disabled in the gui
+    pub fn with_breakpoint(mut self, breakpoint: Breakpoint) -> Self {
+        self.breakpoints.push(breakpoint);
+        self
+    }
+
+    /// Every live robot gets an additional event sink created by `sink_factory` from the robot name, e.g. `JsonLinesSink` or `MetricsSink`
+    pub fn with_event_sink(mut self, sink_factory: impl Fn(&str) -> Box<dyn EventSink> + 'static) -> Self {
+        self.event_sink_factories.push(Box::new(sink_factory));
+        self
+    }
+
+    /// Simulation will be recorded to the given file, recording can be played back by `OhCrabVisualizer::replay`
+    pub fn with_recording(mut self, recording_path: &str) -> Self {
+        self.recording_path = Some(recording_path.to_owned());
+        self
+    }
+
+    /// Everything robots publish is logged to the given file by `JsonLinesSink`, when several robots run every robot gets its own file
+    pub fn with_event_log(mut self, event_log_path: &str) -> Self {
+        self.event_log_path = Some(event_log_path.to_owned());
+        self
+    }
+
+    /// Initial world state and everything robots publish is streamed as JSON lines to clients connected to the given local TCP address,
+    /// e.g. `127.0.0.1:7878`. Every line contains robot name and an entry in the same format as the recording.
+    pub fn with_live_stream(mut self, address: &str) -> Self {
+        self.live_stream_address = Some(address.to_owned());
+        self
+    }
+
+    fn create_event_sinks(&self, robot_name: &str, robot_count: usize) -> Result<Vec<Box<dyn EventSink>>, OhCrabVisualizerError> {
+        let mut event_sinks: Vec<Box<dyn EventSink>> = self.event_sink_factories.iter().map(|sink_factory| sink_factory(robot_name)).collect();
+        if let Some(event_log_path) = &self.event_log_path {
+            event_sinks.push(Box::new(JsonLinesSink::new(&path_for_robot(event_log_path, robot_name, robot_count))?));
+        }
+        Ok(event_sinks)
+    }
 }
 
 #[derive(PartialEq, Default, Debug)]
@@ -193,33 +297,105 @@ pub(super) enum ContentDisplayOptions {
     No 
 }
 
-impl OhCrabVisualizer {
-    pub fn new(robot_creator: impl RobotCreator, mut world_generator: OxAgWorldGenerator, config: OhCrabVisualizerConfig) -> OhCrabVisualizer {
-        let (robot_sender, robot_receiver) = mpsc::channel::<ChannelItem>();
-        let (map_sender, map_receiver) = mpsc::channel::<InitStateChannelItem>();
+/// Decides which map is rendered in the tile grid
+/// 
+#[derive(PartialEq, Default, Debug, Clone, Copy)]
+pub(super) enum MapDisplayOptions {
+    #[default] TrueWorld, // whole world as obtained from `debug` interface
+    RobotKnowledge,       // only tiles present in `robot_map`
+    Diff                  // whole world with undiscovered and outdated tiles highlighted
+}
 
-        let visualizer_data_sender = VisualizerEventListener::new(robot_sender, config.use_sound);
-        let robot = robot_creator.create(visualizer_data_sender);
-        let visualizable_robot = VisualizableRobot::new(robot, map_sender);
+/// Decides how tiles are shaded on top of their textures
+/// 
+#[derive(PartialEq, Default, Debug)]
+pub(super) enum TileShadingOptions {
+    #[default] No,
+    Elevation,  // lower tiles are darker
+    WalkCost    // cost of walking to the tile under the current weather, from green to red
+}
+
+/// Decides which per tile statistic is drawn over the tile grid
+/// 
+#[derive(PartialEq, Default, Debug, Clone, Copy)]
+pub(super) enum HeatmapDisplayOptions {
+    #[default] No,
+    Visits,         // how many times robot moved to the tile
+    EnergySpent,    // energy consumed while robot stood on the tile
+    ContentDensity  // known tiles with chosen content around the tile
+}
+
+/// Decides which time series is plotted in the charts window
+/// 
+#[derive(PartialEq, Default, Debug)]
+pub(super) enum ChartDisplayOptions {
+    #[default] Energy,  // energy level at the end of every tick
+    EnergyFlow,         // energy consumed and recharged during every tick
+    Score,
+    Backpack            // number of items in the backpack
+}
 
-        let runner = Runner::new(Box::new(visualizable_robot), &mut world_generator).expect("Runner creation failed");
+/// Decides which actions are listed in the history window
+/// 
+#[derive(PartialEq, Default, Debug)]
+pub(super) enum HistoryFilterOptions {
+    #[default] All,
+    Succeeded,  // includes actions inferred from events
+    Failed
+}
 
-        OhCrabVisualizer {
-            runner: runner,
-            robot_receiver: robot_receiver,
-            map_receiver,
-            action_cache: Cache::new(50),
+impl OhCrabVisualizer {
+    /// Fails when the runner, the recording or event log file or the live stream cannot be created
+    pub fn new(robot_creator: impl RobotCreator, mut world_generator: OxAgWorldGenerator, config: OhCrabVisualizerConfig) -> Result<OhCrabVisualizer, OhCrabVisualizerError> {
+        let event_sinks = config.create_event_sinks("Robot", 1)?;
+        let robot = RobotSession::live("Robot".to_owned(), ROBOT_COLORS[0], &robot_creator, &mut world_generator, &config.settings, config.use_sound, config.recording_path.clone(), event_sinks)?;
+        OhCrabVisualizer::build(vec![robot], config)
+    }
+
+    /// Creates visualizer running several robots side by side. Every robot gets its own `Runner` and its own world,
+    /// all the worlds are created by the same world generator, so the robots start in identical worlds.
+    /// Sound is played only for the first robot.
+    pub fn new_multi_robot(robot_creators: Vec<(String, Box<dyn RobotCreator>)>, mut world_generator: OxAgWorldGenerator, config: OhCrabVisualizerConfig) -> Result<OhCrabVisualizer, OhCrabVisualizerError> {
+        let robot_count = robot_creators.len();
+        let robots = robot_creators.into_iter().enumerate().map(|(index, (name, robot_creator))| {
+            let recording_path = config.recording_path.as_ref().map(|path| path_for_robot(path, &name, robot_count));
+            let event_sinks = config.create_event_sinks(&name, robot_count)?;
+            RobotSession::live(name, ROBOT_COLORS[index % ROBOT_COLORS.len()], robot_creator.as_ref(), &mut world_generator, &config.settings, config.use_sound && index == 0, recording_path, event_sinks)
+        }).collect::<Result<Vec<_>, _>>()?;
+        OhCrabVisualizer::build(robots, config)
+    }
+
+    /// Creates visualizer that plays back simulation recorded via `OhCrabVisualizerConfig::with_recording`, no robot or runner is needed
+    pub fn replay(recording_path: &str, config: OhCrabVisualizerConfig) -> Result<OhCrabVisualizer, OhCrabVisualizerError> {
+        let robot = RobotSession::replay("Replay".to_owned(), ROBOT_COLORS[0], recording_path, &config.settings, config.recording_path.clone())?;
+        OhCrabVisualizer::build(vec![robot], config)
+    }
+
+    fn build(mut robots: Vec<RobotSession>, config: OhCrabVisualizerConfig) -> Result<OhCrabVisualizer, OhCrabVisualizerError> {
+        if let Some(address) = &config.live_stream_address {
+            let live_stream = LiveStream::start(address)?;
+            for robot in robots.iter_mut() {
+                robot.attach_live_stream(live_stream.clone());
+            }
+        }
+        let mut breakpoints = Breakpoints::default();
+        for breakpoint in config.breakpoints {
+            breakpoints.add(breakpoint);
+        }
+        Ok(OhCrabVisualizer {
+            robots,
+            selected_robot: 0,
             gui: Gui::default(),
+            playback: Playback::new(&config.run_mode),
+            breakpoints,
             run_mode: config.run_mode,
             tick_counter: 0,
-            world_state: WorldState::empty(),
-            world_time: WorldTime::default(),
-            visualization_state: VisualizationState::default(),
-            world_tick_in_progress: false,
+            visualization_state: VisualizationState { settings: config.settings, ..VisualizationState::default() },
             egui_images: EguiImages::init(),
             ggez_images: GgezImages::empty(),
+            minimap: Minimap::default(),
             rng: rand::thread_rng()
-        }
+        })
     }
 
     pub fn simulate(&mut self) -> Result<(), OhCrabVisualizerError> {
@@ -227,10 +403,10 @@ impl OhCrabVisualizer {
             RunMode::Interactive => Err(OhCrabVisualizerError::ConfigurationError("Cannot run simulation on interactively configured visualizer. To run simulation, set run_mode to RunMode::Noninteractive(total_ticks)".to_string())),
             RunMode::NonInteractive(total_ticks) => {
                 for _ in 0..total_ticks {
-                    match self.runner.game_tick() {
-                        Ok(_) => {}
-                        Err(robot_err) => { return Err(OhCrabVisualizerError::RobotLibError(robot_err)); } 
-                    } 
+                    self.tick_counter += 1;
+                    for robot in self.robots.iter_mut() {
+                        robot.simulate_tick(self.tick_counter)?;
+                    }
                 }
                 Ok(())
             }
@@ -238,11 +414,13 @@ impl OhCrabVisualizer {
     }
 
     pub fn run(mut self) -> Result<(), OhCrabVisualizerError> {
+        let layout = &self.visualization_state.settings.layout;
         let context_builder = ggez::ContextBuilder::new("OhCrabWorld", "OhCrab")
-            .add_resource_path("./assets")
+            .add_resource_path(&self.visualization_state.settings.assets.resource_path)
             .window_mode(ggez::conf::WindowMode::default()
             .resizable(true)
-            .maximized(true));
+            .dimensions(layout.window_width, layout.window_height)
+            .maximized(layout.window_maximized));
 
         match context_builder.build() {
             Ok((ctx, event_loop)) => {
@@ -254,101 +432,126 @@ impl OhCrabVisualizer {
         }
     }
 
-    fn update_riz_messages_for_tick(&mut self) {
-        match self.world_state.rizzler_messages.choose(&mut self.rng) {
-            Some(message) => {self.world_state.rizler_message = Some(message.clone())},
-            None => {},
+    /// Runs the simulation without opening a window and renders the world grid of the selected robot after every tick,
+    /// frames are written as configured in `FrameExportConfig`. Live simulation needs `RunMode::NonInteractive(total_ticks)`,
+    /// replay is exported until the recording ends.
+    pub fn export_frames(mut self, export_config: FrameExportConfig) -> Result<(), OhCrabVisualizerError> {
+        let replays_only = self.robots.iter().all(|robot| robot.is_replay());
+        if let (RunMode::Interactive, false) = (&self.run_mode, replays_only) {
+            return Err(OhCrabVisualizerError::ConfigurationError("Cannot export frames of interactively configured live simulation. To export frames, set run_mode to RunMode::Noninteractive(total_ticks)".to_string()));
+        }
+        let mut exporter = FrameExporter::new(export_config, &self.visualization_state.settings.assets.resource_path)?;
+        let mut no_breakpoints = Breakpoints::default();
+
+        self.do_world_tick()?;
+        for robot in self.robots.iter_mut() {
+            robot.init_state()?;
+        }
+        loop {
+            while self.world_tick_in_progress() {
+                for robot in self.robots.iter_mut() {
+                    robot.process_robotic_lib_event(self.tick_counter, &mut no_breakpoints)?;
+                }
+            }
+            if let Some(world_map) = &self.selected_robot().world_state.world_map {
+                exporter.export_frame(self.tick_counter, world_map, &self.get_robot_markers())?;
+            }
+            if self.simulation_should_end() {
+                return Ok(());
+            }
+            self.do_world_tick()?;
         }
-        self.world_state.rizzler_messages.clear();
     }
 
-    fn update_energy_difference_for_tick(&mut self) {
-        self.world_state.previous_tick_energy_difference = self.world_state.current_tick_energy_difference;
-        self.world_state.current_tick_energy_difference = 0;
+    fn selected_robot(&self) -> &RobotSession {
+        &self.robots[self.selected_robot]
     }
 
     fn do_world_tick(&mut self) -> Result<(), OhCrabVisualizerError> {
-        self.update_energy_difference_for_tick();
-        self.update_riz_messages_for_tick();
-        let res = self.runner.game_tick();
+        for robot in self.robots.iter_mut() {
+            robot.do_world_tick(self.tick_counter, &mut self.rng)?;
+        }
         self.tick_counter += 1;
-        match res {
-            Ok(_) => {
-                if self.visualization_state.should_focus_on_robot {
-                    self.focus_on_robot();
-                }
-                self.world_tick_in_progress = true;
-                Ok(())
-            },
-            Err(robot_err) => { return Err(OhCrabVisualizerError::RobotLibError(robot_err)); } 
-        } 
+        self.playback.tick_started();
+        if self.visualization_state.should_focus_on_robot {
+            self.focus_on_robot();
+        }
+        Ok(())
+    }
+
+    fn world_tick_in_progress(&self) -> bool {
+        self.robots.iter().any(|robot| robot.world_tick_in_progress)
     }
 
     fn init_state(&mut self, canvas_size: f32)  -> Result<(), OhCrabVisualizerError> {
         println_d!("VISUALIZER UPDATE, doing first world tick.");
         self.do_world_tick()?;
         install_image_loaders(&self.gui.ctx());
-        let received_map = self.map_receiver.try_recv();
-
-        // self.world_state.backpack.insert(Content::Rock(0), 5);
-        // self.world_state.backpack.insert(Content::Tree(0), 3);
-        // self.world_state.backpack.insert(Content::Garbage(0), 10);
-        // self.world_state.backpack.insert(Content::Fire, 1);
-        // self.world_state.backpack.insert(Content::Coin(0), 50);
-        // self.world_state.backpack.insert(Content::Bin(0..10), 2);
-        // self.world_state.backpack.insert(Content::Crate(0..10), 3);
-        // self.world_state.backpack.insert(Content::Bank(0..10), 1);
-        // self.world_state.backpack.insert(Content::Water(0), 8);
-        // self.world_state.backpack.insert(Content::Market(0), 1);
-        // self.world_state.backpack.insert(Content::Fish(0), 15);
-        // self.world_state.backpack.insert(Content::Building, 2);
-        // self.world_state.backpack.insert(Content::Bush(0), 5);
-        // self.world_state.backpack.insert(Content::JollyBlock(0), 2);
-        // self.world_state.backpack.insert(Content::Scarecrow, 1);
-        match received_map {
-            Ok(item) => {
-                self.visualization_state.grid_canvas_properties = GridCanvasProperties::build(canvas_size, item.state.world_map.len());
-                let robot_pos = item.state.robot_position;
-                println_d!("VISUALIZER RECEIVED MAP with robot position {:?} and robot energy {:?}", (robot_pos.x, robot_pos.y), item.state.robot_energy);
-                self.world_state.world_map = Some(item.state.world_map);
-                self.world_state.robot_position = Some(robot_pos);
-                self.world_state.robot_energy = item.state.robot_energy;
-                self.focus_on_robot();
-                Ok(())
-            }
-            Err(_) => todo!(),
+
+        let mut world_dimension = 0;
+        for robot in self.robots.iter_mut() {
+            world_dimension = robot.init_state()?;
         }
+        self.visualization_state.grid_canvas_properties = GridCanvasProperties::build(canvas_size, world_dimension, &self.visualization_state.settings);
+        self.focus_on_robot();
+        Ok(())
     }
 
     fn focus_on_robot(&mut self) {
-        if let Some(robot_pos) = &self.world_state.robot_position{
+        if let Some(robot_pos) = &self.robots[self.selected_robot].world_state.robot_position{
             println_d!("Focusing on robot on position {:?}", robot_pos);
-            let world_dimension = self.visualization_state.grid_canvas_properties.world_dimension;
-            // x
-            let half_of_columns_to_display = self.visualization_state.grid_canvas_properties.num_columns_to_display() / 2;
-            if robot_pos.x <= (world_dimension - half_of_columns_to_display) && robot_pos.x >= half_of_columns_to_display { // prevent moving camera when robot is close to edge 
-                self.visualization_state.offset_x = f32::max(0.0, robot_pos.x as f32  - half_of_columns_to_display as f32);
+            // camera stops at the world edge, robot close to the edge is not in the middle
+            self.visualization_state.center_on(robot_pos);
+            println_d!("Focused");
+        }
+    }
+
+    /// Manual camera movement turns off the focus, it would move the camera back in the next tick
+    fn pan_camera(&mut self, tiles_x: f32, tiles_y: f32) {
+        self.visualization_state.should_focus_on_robot = false;
+        self.visualization_state.pan(tiles_x, tiles_y);
+    }
+
+    fn handle_hotkey(&mut self, keycode: KeyCode) -> Result<(), OhCrabVisualizerError> {
+        match keycode {
+            KeyCode::Left | KeyCode::A => self.pan_camera(-CAMERA_PAN_STEP, 0.0),
+            KeyCode::Right | KeyCode::D => self.pan_camera(CAMERA_PAN_STEP, 0.0),
+            KeyCode::Up | KeyCode::W => self.pan_camera(0.0, -CAMERA_PAN_STEP),
+            KeyCode::Down | KeyCode::S => self.pan_camera(0.0, CAMERA_PAN_STEP),
+            KeyCode::Equals | KeyCode::Plus | KeyCode::NumpadAdd => self.visualization_state.zoom(ZOOM_STEP),
+            KeyCode::Minus | KeyCode::NumpadSubtract => self.visualization_state.zoom(1.0 / ZOOM_STEP),
+            KeyCode::C => self.focus_on_robot(),
+            KeyCode::Z => self.zoom_on_robot(),
+            KeyCode::F => self.visualization_state.should_focus_on_robot = !self.visualization_state.should_focus_on_robot,
+            KeyCode::Space => {
+                if self.playback.halted_at_breakpoint {
+                    self.playback.continue_from_breakpoint();
+                }
+                else if self.is_live() {
+                    self.playback.toggle();
+                }
             }
-            // y
-            let half_of_rows_to_display =  self.visualization_state.grid_canvas_properties.num_rows_to_display() /2 ;
-            if robot_pos.y <= ( world_dimension - half_of_rows_to_display) && robot_pos.y >= half_of_rows_to_display { // prevent moving camera when robot is close to edge 
-                self.visualization_state.offset_y = f32::max(0.0, robot_pos.y as f32 - half_of_rows_to_display as f32) ;
+            KeyCode::T | KeyCode::Return => {
+                // same conditions as for the `Do tick` button
+                if self.is_live() && !self.world_tick_in_progress() && !self.playback.is_playing && !self.playback.halted_at_breakpoint {
+                    self.do_world_tick()?;
+                }
             }
-
-            println_d!("Focused");
+            _ => {}
         }
+        Ok(())
     }
 
     fn zoom_on_robot(&mut self) {
-        if let Some(_) = &self.world_state.robot_position{
-            self.visualization_state.grid_canvas_properties.tile_size = DEFAULT_TILE_SIZE;
+        if let Some(_) = &self.selected_robot().world_state.robot_position{
+            self.visualization_state.grid_canvas_properties.tile_size = self.visualization_state.settings.tiles.default_tile_size;
             self.focus_on_robot();
         }
     }
 
     fn move_camera_if_world_is_zoomed_out(&mut self) {
         // move camera if the world is zoomed out
-        if let Some(world_map) = &self.world_state.world_map {
+        if let Some(world_map) = &self.robots[self.selected_robot].world_state.world_map {
             let (scroll_limit_x, scroll_limit_y) = self.visualization_state.get_scroll_limit(world_map.len());
             self.visualization_state.offset_x = f32::min(self.visualization_state.offset_x, scroll_limit_x);
             self.visualization_state.offset_y = f32::min(self.visualization_state.offset_y, scroll_limit_y);
@@ -356,30 +559,33 @@ impl OhCrabVisualizer {
     }
 
     fn simulation_should_end(&self) -> bool {
+        if self.robots.iter().all(|robot| robot.replay_finished()) {
+            return true;
+        }
         match self.run_mode {
             RunMode::Interactive => false,
             RunMode::NonInteractive(total_ticks) => self.tick_counter >= total_ticks,
         }
     }
 
-    fn is_interactive(&self) -> bool {
-        match self.run_mode {
-            RunMode::Interactive => true,
-            RunMode::NonInteractive(_) => false,
-        }
+    fn is_live(&self) -> bool {
+        self.selected_robot().world_history.is_live()
     }
 
     fn add_control_panel(&mut self, gui_ctx: &mut GuiContext) -> Result<(), OhCrabVisualizerError> {
         let mut res: Result<(), OhCrabVisualizerError> = Ok(());
+        let is_live = self.is_live();
+        let world_tick_in_progress = self.world_tick_in_progress();
         egui::Window::new("Scroll world")
         .default_pos((5.0, 10.0))
         .show(&gui_ctx, |ui: &mut egui::Ui| {
-            if let Some(world_map) = &self.world_state.world_map {
+            if let Some(world_map) = &self.robots[self.selected_robot].world_state.world_map {
                 let (scroll_limit_x, scroll_limit_y) = self.visualization_state.get_scroll_limit(world_map.len());
                 ui.add(egui::Slider::new(&mut self.visualization_state.offset_x, 0.0..=scroll_limit_x));
                 ui.add(egui::Slider::new(&mut self.visualization_state.offset_y, scroll_limit_y..=0.0).orientation(egui::SliderOrientation::Vertical));
-                ui.add(egui::Slider::new(&mut self.visualization_state.grid_canvas_properties.tile_size, self.visualization_state.grid_canvas_properties.tile_size_min..=TILE_SIZE_MAX));
+                ui.add(egui::Slider::new(&mut self.visualization_state.grid_canvas_properties.tile_size, self.visualization_state.grid_canvas_properties.tile_size_min..=self.visualization_state.settings.tiles.max_tile_size));
                 ui.add(egui::Checkbox::new(&mut self.visualization_state.should_focus_on_robot, "Focus on robot"));
+                ui.add(egui::Checkbox::new(&mut self.visualization_state.show_robot_paths, "Show robot paths"));
             }
             if ui.add(egui::Button::new("Center on robot")).clicked() {
                 self.focus_on_robot();
@@ -388,24 +594,81 @@ impl OhCrabVisualizer {
                 self.zoom_on_robot();
             }
 
-            if self.is_interactive() {
-                if self.world_tick_in_progress {
+            ui.separator();
+            ui.horizontal(|ui| {
+                if self.playback.halted_at_breakpoint {
+                    if ui.add(egui::Button::new("Continue")).clicked() {
+                        self.playback.continue_from_breakpoint();
+                    }
+                }
+                else {
+                    let play_label = if self.playback.is_playing { "Pause" } else { "Play" };
+                    if ui.add_enabled(is_live, egui::Button::new(play_label)).clicked() {
+                        self.playback.toggle();
+                    }
+                }
+                if !is_live {
+                    if ui.add_enabled(false, egui::Button::new("Viewing history")).clicked() {
+                        unreachable!();
+                    }
+                }
+                else if world_tick_in_progress {
                     if ui.add_enabled(false, egui::Button::new("Tick in progress")).clicked() {
                         unreachable!();
                     }
                 }
                 else {
-                    if ui.add(egui::Button::new("Do tick")).clicked() {
+                    if ui.add_enabled(!self.playback.is_playing, egui::Button::new("Do tick")).clicked() {
                         res = self.do_world_tick();
                     }
                 }
-            }
+            });
+            ui.add_enabled(!self.playback.unlimited_speed, egui::Slider::new(&mut self.playback.ticks_per_second, MIN_TICKS_PER_SECOND..=MAX_TICKS_PER_SECOND).text("ticks per second"));
+            ui.add(egui::Checkbox::new(&mut self.playback.unlimited_speed, "Unlimited speed"));
+            ui.horizontal(|ui| {
+                ui.label("Run until tick: ");
+                ui.add(egui::DragValue::new(&mut self.playback.run_until_input).clamp_range(self.tick_counter..=usize::MAX));
+                if ui.add_enabled(is_live && self.playback.run_until_input > self.tick_counter, egui::Button::new("Run")).clicked() {
+                    self.playback.run_until(self.playback.run_until_input);
+                }
+            });
+            ui.separator();
             ui.label("Content: ");
             ui.horizontal(|ui| {
                 ui.radio_value(&mut self.visualization_state.content_display_option, ContentDisplayOptions::Images, "Images");
                 ui.radio_value(&mut self.visualization_state.content_display_option, ContentDisplayOptions::Lables, "Labels");
-                ui.radio_value(&mut self.visualization_state.content_display_option, ContentDisplayOptions::No, "None"); 
+                ui.radio_value(&mut self.visualization_state.content_display_option, ContentDisplayOptions::No, "None");
+            });
+            ui.label("Map: ");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.visualization_state.map_display_option, MapDisplayOptions::TrueWorld, "True world");
+                ui.radio_value(&mut self.visualization_state.map_display_option, MapDisplayOptions::RobotKnowledge, "Robot knowledge");
+                ui.radio_value(&mut self.visualization_state.map_display_option, MapDisplayOptions::Diff, "Diff");
             });
+            ui.label("Shading: ");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.visualization_state.tile_shading_option, TileShadingOptions::No, "None");
+                ui.radio_value(&mut self.visualization_state.tile_shading_option, TileShadingOptions::Elevation, "Elevation");
+                ui.radio_value(&mut self.visualization_state.tile_shading_option, TileShadingOptions::WalkCost, "Walk cost");
+            });
+            ui.add(egui::Checkbox::new(&mut self.visualization_state.show_time_tint, "Day/night and weather tint"));
+            ui.label("Heatmap: ");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.visualization_state.heatmap_display_option, HeatmapDisplayOptions::No, "None");
+                ui.radio_value(&mut self.visualization_state.heatmap_display_option, HeatmapDisplayOptions::Visits, "Visits");
+                ui.radio_value(&mut self.visualization_state.heatmap_display_option, HeatmapDisplayOptions::EnergySpent, "Energy spent");
+                ui.radio_value(&mut self.visualization_state.heatmap_display_option, HeatmapDisplayOptions::ContentDensity, "Content density");
+            });
+            if self.visualization_state.heatmap_display_option == HeatmapDisplayOptions::ContentDensity {
+                let contents = heatmap_contents();
+                egui::ComboBox::from_label("Content")
+                    .selected_text(draw_utils::remove_content_between_parentheses(&format!("{:?}", contents[self.visualization_state.heatmap_content])))
+                    .show_ui(ui, |ui| {
+                        for (index, content) in contents.iter().enumerate() {
+                            ui.selectable_value(&mut self.visualization_state.heatmap_content, index, draw_utils::remove_content_between_parentheses(&format!("{:?}", content)));
+                        }
+                    });
+            }
         });
 
         if res.is_err() {
@@ -414,153 +677,180 @@ impl OhCrabVisualizer {
         Ok(())
     }
 
+    fn add_timeline_panel(&mut self, gui_ctx: &mut GuiContext) {
+        let latest_tick = self.selected_robot().world_history.latest_tick();
+        let displayed_tick = self.selected_robot().world_history.displayed_tick();
+        let is_live = self.is_live();
+        let mut target_tick = displayed_tick;
+        egui::Window::new("Timeline")
+        .default_pos((5.0, 250.0))
+        .show(&gui_ctx, |ui: &mut egui::Ui| {
+            if latest_tick <= 1 {
+                ui.label("No history yet.");
+                return;
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(target_tick > 1, egui::Button::new("Step back")).clicked() {
+                    target_tick -= 1;
+                }
+                if ui.add_enabled(target_tick < latest_tick, egui::Button::new("Step forward")).clicked() {
+                    target_tick += 1;
+                }
+                if ui.add_enabled(target_tick < latest_tick, egui::Button::new("Back to live")).clicked() {
+                    target_tick = latest_tick;
+                }
+            });
+            ui.add(egui::Slider::new(&mut target_tick, 1..=latest_tick).text("tick"));
+            ui.horizontal(|ui| {
+                ui.label("Jump to tick: ");
+                ui.add(egui::DragValue::new(&mut self.visualization_state.jump_to_tick).clamp_range(1..=latest_tick));
+                if ui.add(egui::Button::new("Jump")).clicked() {
+                    target_tick = self.visualization_state.jump_to_tick;
+                }
+            });
+            if !is_live {
+                ui.strong("Viewing history, simulation is paused.");
+            }
+        });
+
+        if target_tick != displayed_tick {
+            // all the robots are moved in lockstep
+            for robot in self.robots.iter_mut() {
+                robot.go_to_tick(target_tick);
+            }
+        }
+    }
+
     fn register_egui_windows(&mut self, ctx: &mut ggez::Context) -> Result<(), OhCrabVisualizerError> {
         let gui_ctx = &mut self.gui.ctx();
         gui_ctx.set_visuals(Visuals::dark());
-       
+
+        let panels = self.visualization_state.settings.panels.clone();
         self.add_control_panel(gui_ctx)?;
-        egui_utils::draw_backpack(gui_ctx, &self.visualization_state, &self.world_state.backpack, &self.egui_images);
-        egui_utils::draw_time(gui_ctx, &self.visualization_state, &self.world_time, self.tick_counter, self.simulation_should_end(), &self.egui_images);
-        egui_utils::draw_energy_bar(gui_ctx, &self.visualization_state, self.world_state.robot_energy, self.world_state.previous_tick_energy_difference, &self.egui_images);
-        let cached_actions = self.action_cache.get_recent_actions(self.action_cache.get_size()).unwrap();
-        egui_utils::draw_history_cache(gui_ctx, &self.visualization_state, &cached_actions, &self.egui_images);
-        egui_utils::draw_rizler_message(gui_ctx, &self.visualization_state, &self.world_state.rizler_message);
-        
+        if panels.timeline {
+            self.add_timeline_panel(gui_ctx);
+        }
+        if panels.robots && self.robots.len() > 1 {
+            egui_utils::draw_robot_selector(gui_ctx, &self.visualization_state, &self.robots, &mut self.selected_robot);
+        }
+        let robot = &self.robots[self.selected_robot];
+        if panels.backpack {
+            egui_utils::draw_backpack(gui_ctx, &self.visualization_state, &robot.world_state.backpack, &self.egui_images);
+        }
+        if panels.time {
+            egui_utils::draw_time(gui_ctx, &self.visualization_state, &robot.world_time, robot.world_history.displayed_tick(), self.simulation_should_end(), &self.egui_images);
+        }
+        if panels.energy {
+            egui_utils::draw_energy_bar(gui_ctx, &self.visualization_state, robot.world_state.robot_energy, robot.world_state.previous_tick_energy_difference, &self.egui_images);
+        }
+        if panels.history_cache {
+            egui_utils::draw_history_cache(gui_ctx, &mut self.visualization_state, &robot.action_history, &self.egui_images);
+        }
+        if panels.rizzler {
+            egui_utils::draw_rizler_message(gui_ctx, &self.visualization_state, &robot.world_state.rizler_message);
+        }
+        if panels.tile_inspector {
+            self.add_tile_inspector(gui_ctx);
+        }
+        if panels.minimap {
+            self.add_minimap(gui_ctx);
+        }
+        if panels.charts {
+            self.add_charts_panel(gui_ctx);
+        }
+        if panels.breakpoints {
+            egui_utils::draw_breakpoints(gui_ctx, &self.visualization_state, &mut self.breakpoints, self.playback.halted_at_breakpoint);
+        }
+
         self.gui.update(ctx);
         Ok(())
     }
 
-    #[inline]
-    fn process_time_changed_event(&mut self, env_conditions: EnvironmentalConditions) {
-        println_d!("VISUALIZER: received EVENT time changed {:?}", (env_conditions));
-        self.world_time.update_from_env_conditions(&env_conditions);
-    }
-
-    #[inline]
-    fn process_day_changed_event(&mut self, env_conditions: EnvironmentalConditions) {
-        println_d!("VISUALIZER: received EVENT day changed {:?}", (env_conditions));
-        self.world_time.update_from_env_conditions(&env_conditions);
-        self.world_time.day_counter +=1;
-    }
-
-    #[inline]
-    fn process_energy_regarged_event(&mut self, amount: usize) {
-        println_d!("VISUALIZER: received EVENT energy recharged {:?}", (amount));
-        self.world_state.robot_energy += amount;
-        self.world_state.current_tick_energy_difference += amount as i32;
-    }
-
-    #[inline]
-    fn process_energy_consumed_event(&mut self, amount: usize) {
-        println_d!("VISUALIZER: received EVENT energy consumed {:?}", (amount));
-        let to_subtract = usize::min(amount, self.world_state.robot_energy); // to prevent subtract with overflow
-        self.world_state.robot_energy -= to_subtract;
-        self.world_state.current_tick_energy_difference -= to_subtract as i32;
-    }
-
-    #[inline]
-    fn process_moved_event(&mut self, robot_x: usize, robot_y :usize) {
-        println_d!("VISUALIZER: received robot moved {:?}", (robot_x, robot_y));
-        self.world_state.robot_position = Some(Coord{x:robot_x, y:robot_y });
+    fn add_minimap(&mut self, gui_ctx: &mut GuiContext) {
+        let tick_in_progress = self.world_tick_in_progress();
+        let robot = &self.robots[self.selected_robot];
+        if let Some(world_map) = &robot.world_state.world_map {
+            let drawn_for = (self.selected_robot, robot.world_history.displayed_tick(), self.visualization_state.map_display_option);
+            self.minimap.update(gui_ctx, drawn_for, tick_in_progress, world_map, &robot.world_state.robot_map);
+        }
+        let target_tile = egui_utils::draw_minimap(gui_ctx, &self.visualization_state, &self.minimap, &self.get_robot_markers());
+        if let Some(target_tile) = target_tile {
+            // jumping away from the robot would be undone by the focus in the next tick
+            self.visualization_state.should_focus_on_robot = false;
+            self.visualization_state.center_on(&target_tile);
+        }
     }
 
-    #[inline]
-    fn process_tile_content_update_event(&mut self, tile: Tile, tile_x: usize, tile_y: usize) {
-        println_d!("VISUALIZER: received tile content update.");
-        if let Some(world_map) = &mut self.world_state.world_map{
-            world_map[tile_y][tile_x] = tile;
+    fn add_charts_panel(&mut self, gui_ctx: &mut GuiContext) {
+        let displayed_tick = self.selected_robot().world_history.displayed_tick();
+        let export_clicked = egui_utils::draw_charts(gui_ctx, &mut self.visualization_state, &self.robots, displayed_tick);
+        if export_clicked {
+            let robot = &self.robots[self.selected_robot];
+            let csv_path = format!("./charts/{}_tick{}.csv", robot.name.replace(' ', "_"), self.tick_counter);
+            self.visualization_state.chart_export_message = match robot.time_series.write_csv(&csv_path) {
+                Ok(_) => Some(format!("Exported to {}", csv_path)),
+                Err(err) => Some(format!("Export failed: {:?}", err)),
+            };
         }
     }
 
-    #[inline]
-    fn process_added_to_backpack_event(&mut self, content: Content, amount: usize) {
-        println_d!("VISUALIZER: added to backpack: {:?}, {:?}.", content, amount);
-        *self.world_state.backpack.entry(content.clone()).or_insert(0) += amount;
-        println_d!("   current amount {:?} of {:?} after add", self.world_state.backpack.get(&content), content.to_string());
+    fn add_tile_inspector(&mut self, gui_ctx: &mut GuiContext) {
+        let robot = &self.robots[self.selected_robot];
+        if let (Some(coord), Some(world_map)) = (self.visualization_state.inspected_tile(), &robot.world_state.world_map) {
+            let tile = &world_map[coord.y][coord.x];
+            let known_tile = robot.world_state.robot_map.as_ref().and_then(|robot_map| robot_map[coord.y][coord.x].as_ref());
+            let robot_tile = robot.world_state.robot_position.as_ref().map(|robot_pos| &world_map[robot_pos.y][robot_pos.x]);
+            let is_pinned = self.visualization_state.pinned_tile.is_some();
+            let unpin = egui_utils::draw_tile_inspector(gui_ctx, &self.visualization_state, coord, tile, known_tile, robot_tile, &robot.world_time.env_conditions, is_pinned);
+            if unpin {
+                self.visualization_state.pinned_tile = None;
+            }
+        }
     }
 
-    #[inline]
-    fn process_removed_from_backpack_event(&mut self,  content: Content, amount: usize) {
-        println!("VISUALIZER: removed from backpack: {:?}, {:?}.", content, amount);
-        if let Some(current_amount) = self.world_state.backpack.get_mut(&content) {
-            if *current_amount > amount {
-                *current_amount -= amount;
-                println_d!("   current amount {:?} of {:?} after remove", *current_amount, content.to_string());
-            } else {
-                self.world_state.backpack.remove(&content);
+    fn process_robotic_lib_event(&mut self) -> Result<(), OhCrabVisualizerError> {
+        for robot in self.robots.iter_mut() {
+            if let Some(breakpoint_hit) = robot.process_robotic_lib_event(self.tick_counter, &mut self.breakpoints)? {
+                println_d!("VISUALIZER: breakpoint hit in tick {} - {}", self.tick_counter, breakpoint_hit);
+                self.breakpoints.last_hit = Some(format!("tick {} - {}", self.tick_counter, breakpoint_hit));
+                self.playback.halt_at_breakpoint();
             }
         }
+        if !self.world_tick_in_progress() && self.playback.should_tick(self.tick_counter) {
+            self.do_world_tick()?;
+        }
+        Ok(())
     }
 
-    #[inline]
-    fn process_interface_invocation_record(&mut self, interface_invocation: InterfaceInvocation) {
-        println_d!("VISULAZER: received interface invocation: {:?}", interface_invocation);
-
-        // history cache
-        self.action_cache.add_record(interface_invocation.interface_action, (interface_invocation.robot_position.x, interface_invocation.robot_position.y));
-       
-        //rizzler
-        if let Some(meesage) = interface_invocation.riz_message {
-            self.world_state.rizzler_messages.push(meesage);
+    /// Robots are drawn in their colors, the selected one is drawn last so that it is on top
+    fn get_robot_markers(&self) -> Vec<RobotMarker<'_>> {
+        let mut markers: Vec<RobotMarker<'_>> = Vec::new();
+        for (index, robot) in self.robots.iter().enumerate() {
+            if let Some(position) = &robot.world_state.robot_position {
+                let (r, g, b) = robot.color;
+                let marker = RobotMarker {
+                    position: position.clone(),
+                    color: Color::from_rgb(r, g, b),
+                    is_selected: index == self.selected_robot,
+                    plan: &robot.world_state.robot_plan,
+                    travelled_path: &robot.world_state.travelled_path
+                };
+                if marker.is_selected { markers.push(marker); } else { markers.insert(0, marker); }
+            }
         }
+        markers
     }
+}
 
-    fn process_robotic_lib_event(&mut self) -> Result<(), OhCrabVisualizerError> {
-                let received_state = self.robot_receiver.try_recv();
-
-                match received_state {
-                    Ok(channel_item) => {
-                        match  channel_item {
-                            ChannelItem::EventChannelItem(event) => {
-                                match event {
-                                    // RobotEvent::Ready => todo!(),
-                                    // RobotEvent::Terminated => todo!(),
-                                    RobotEvent::TimeChanged(env_conditions) => {
-                                        self.process_time_changed_event(env_conditions);
-                                    }
-                                    RobotEvent::DayChanged(env_conditions) => {
-                                        self.process_day_changed_event(env_conditions);
-                                    }
-                                    RobotEvent::EnergyRecharged(amount) => {
-                                        self.process_energy_regarged_event(amount);
-                                    },
-                                    RobotEvent::EnergyConsumed(amount) => {
-                                        self.process_energy_consumed_event(amount);
-                                    },
-                                    RobotEvent::Moved(_, (robot_y, robot_x)) => { // BEWARE: library has x and y switched in Move event
-                                        self.process_moved_event(robot_x, robot_y);
-                                    }
-                                    RobotEvent::TileContentUpdated(tile, (tile_y, tile_x)) => {
-                                        self.process_tile_content_update_event(tile, tile_x, tile_y);
-                                    }
-                                    RobotEvent::AddedToBackpack(content, amount) => {
-                                        self.process_added_to_backpack_event(content, amount);
-                                    }
-                                    RobotEvent::RemovedFromBackpack(content, amount) => {
-                                        self.process_removed_from_backpack_event(content, amount);
-                                    }
-                                    _ => {
-                                        println_d!("VISUALIZER: {:?}", event);
-                                    }
-                                }
-                            }
-                            ChannelItem::InterfaceChannelItem(interface_invocation) => {
-                                self.process_interface_invocation_record(interface_invocation);
-                            }
-                        }
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {
-                        self.world_tick_in_progress = false;
-                        if !self.is_interactive() {
-                            self.do_world_tick()?;
-                        }
-                    }
-                    Err(error) => {
-                        println_d!("VISUALIZER: try receive error: {:?}.", error);
-                        return Err(OhCrabVisualizerError::DataError(DataChannelError::TryRecvError(error)));
-                    }
-                }
-                Ok(())
+/// When several robots are recorded or logged, every robot gets its own file with robot name appended to the file name
+fn path_for_robot(path: &str, robot_name: &str, robot_count: usize) -> String {
+    if robot_count <= 1 {
+        return path.to_owned();
+    }
+    let robot_name = robot_name.replace(' ', "_");
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains('/') && !extension.contains('\\') => format!("{}_{}.{}", stem, robot_name, extension),
+        _ => format!("{}_{}", path, robot_name),
     }
 }
 
@@ -571,10 +861,19 @@ impl EventHandler<OhCrabVisualizerError> for OhCrabVisualizer {
             let size = f32::min(x, y);
             self.init_state(size)?;
         }
-        
+
         self.register_egui_windows(ctx)?;
         self.move_camera_if_world_is_zoomed_out();
 
+        if !self.is_live() {
+            // simulation is paused while user inspects history
+            return Ok(());
+        }
+
+        if self.playback.halted_at_breakpoint {
+            return Ok(());
+        }
+
         if self.simulation_should_end() {
             //_ctx.request_quit();
             println_d!("empty update");
@@ -585,16 +884,78 @@ impl EventHandler<OhCrabVisualizerError> for OhCrabVisualizer {
         Ok(())
     }
 
+    fn mouse_motion_event(&mut self, _ctx: &mut ggez::Context, x: f32, y: f32, dx: f32, dy: f32) -> Result<(), OhCrabVisualizerError> {
+        self.visualization_state.hovered_tile = if self.gui.ctx().is_pointer_over_area() { None } else { self.visualization_state.tile_at_canvas_position(x, y) };
+
+        if let Some((start_x, start_y)) = self.visualization_state.drag_start {
+            let tile_size = self.visualization_state.grid_canvas_properties.tile_size;
+            if self.visualization_state.is_dragging {
+                self.pan_camera(-dx / tile_size, -dy / tile_size);
+            }
+            else if f32::hypot(x - start_x, y - start_y) > DRAG_THRESHOLD {
+                self.visualization_state.is_dragging = true;
+                self.pan_camera((start_x - x) / tile_size, (start_y - y) / tile_size);
+            }
+        }
+        Ok(())
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut ggez::Context, button: MouseButton, x: f32, y: f32) -> Result<(), OhCrabVisualizerError> {
+        if button == MouseButton::Left && self.tick_counter > 0 && !self.gui.ctx().is_pointer_over_area() {
+            self.visualization_state.drag_start = Some((x, y));
+            self.visualization_state.is_dragging = false;
+        }
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut ggez::Context, button: MouseButton, x: f32, y: f32) -> Result<(), OhCrabVisualizerError> {
+        if button == MouseButton::Left && self.visualization_state.drag_start.take().is_some() {
+            if !self.visualization_state.is_dragging {
+                // clicking the pinned tile again unpins it
+                let clicked_tile = self.visualization_state.tile_at_canvas_position(x, y);
+                self.visualization_state.pinned_tile = if clicked_tile == self.visualization_state.pinned_tile { None } else { clicked_tile };
+            }
+            self.visualization_state.is_dragging = false;
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut ggez::Context, _x: f32, y: f32) -> Result<(), OhCrabVisualizerError> {
+        if self.tick_counter > 0 && !self.gui.ctx().is_pointer_over_area() {
+            let cursor = ctx.mouse.position();
+            self.visualization_state.zoom_at(ZOOM_STEP.powf(y), cursor.x, cursor.y);
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut ggez::Context, input: KeyInput, _repeated: bool) -> Result<(), OhCrabVisualizerError> {
+        if input.keycode == Some(KeyCode::Escape) {
+            ctx.request_quit();
+        }
+        // keys typed into egui widgets are not hotkeys
+        if self.tick_counter == 0 || self.gui.ctx().wants_keyboard_input() {
+            return Ok(());
+        }
+        if let Some(keycode) = input.keycode {
+            self.handle_hotkey(keycode)?;
+        }
+        Ok(())
+    }
+
     fn draw(&mut self, ctx: &mut ggez::Context) -> Result<(), OhCrabVisualizerError> {
-        if let Some(world_map) = &self.world_state.world_map {
+        self.robots[self.selected_robot].update_heat_values(self.visualization_state.heatmap_display_option, self.visualization_state.heatmap_content);
+        let robot_markers = self.get_robot_markers();
+        let robot = &self.robots[self.selected_robot];
+        let world_state = &robot.world_state;
+        if let Some(world_map) = &world_state.world_map {
             let mut canvas = graphics::Canvas::from_frame(ctx, graphics::Color::BLACK);
 
             // draw grid
-            draw_utils::draw_grid(ctx, &mut canvas, &self.visualization_state, world_map, &self.world_state.robot_position, &self.ggez_images)?;
+            draw_utils::draw_grid(ctx, &mut canvas, &self.visualization_state, world_map, &world_state.robot_map, robot.heatmaps.heat_values(), &robot.world_time, &robot_markers, &self.ggez_images)?;
 
             // draw gui
             canvas.draw(&self.gui, DrawParam::default().dest(glam::Vec2::new(400.0, 400.0)));
-            
+
             match canvas.finish(ctx) {
                 Ok(_) => Ok(()),
                 Err(error) => Err(OhCrabVisualizerError::GraphicsLibraryError(error)),
@@ -604,4 +965,4 @@ impl EventHandler<OhCrabVisualizerError> for OhCrabVisualizer {
             Err(OhCrabVisualizerError::DataError(DataChannelError::StateMissingError(format!("Game state is missing when it should be present"))))
         }
     }
-}
\ No newline at end of file
+}
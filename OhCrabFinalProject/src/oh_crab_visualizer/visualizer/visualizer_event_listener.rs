@@ -1,75 +1,316 @@
-use std::sync::mpsc::Sender;
+use std::{cell::RefCell, mem::{self, Discriminant}, rc::Rc, sync::mpsc::Sender};
 
-use oxagaudiotool::OxAgAudioTool;
-use robotics_lib::event::events::Event as RobotEvent;
+use robotics_lib::{event::events::Event as RobotEvent, world::tile::Tile, interface::Direction};
 use rstykrab_cache::Action;
+use serde::{Serialize, Deserialize};
 
-use crate::{oh_crab_visualizer::audio::get_configured_audio_tool, println_d};
+use crate::println_d;
+
+use super::{settings::VisualizerSettings, event_sink::{EventSink, ChannelSink, AudioSink}, serializable::SerializableAction};
 
 use super::Coord;
 
-#[derive(Debug)]
-pub(super) enum ChannelItem {
+/// Data robot publishes during the run, passed to every `EventSink` of the listener
+///
+#[derive(Debug, Clone)]
+pub enum ChannelItem {
     EventChannelItem(RobotEvent),
-    InterfaceChannelItem(InterfaceInvocation)
+    InterfaceChannelItem(InterfaceInvocation),
+    RobotMapChannelItem(RobotMapUpdate),          // tiles of robot's known map changed during the tick, sent at the end of the tick
+    ScoreChannelItem(f32),                        // world score as returned by `get_score`, sent at the end of every tick
+    PlanChannelItem(RobotPlan),                   // path the robot is planning to walk, published by the robot itself
+    AnnotationChannelItem(String),                // free text note published by the robot, e.g. decision it made
+    TickEndedChannelItem                          // robot finished its tick, published by `EventForwardingRobot` after the robot map and the score
+}
+
+/// Path robot intends to walk and the tile it is heading to. Empty plan clears the previously published one.
+///
+#[derive(Debug, Clone, Default)]
+pub struct RobotPlan {
+    pub(super) path: Vec<Coord>,
+    pub(super) target: Option<Coord>
+}
+
+impl RobotPlan {
+    pub(super) fn is_empty(&self) -> bool {
+        self.path.is_empty() && self.target.is_none()
+    }
+
+    /// Tiles of the path as (row, col)
+    pub fn path(&self) -> Vec<(usize, usize)> {
+        self.path.iter().map(|coord| (coord.y, coord.x)).collect()
+    }
+
+    /// Tile robot is heading to as (row, col)
+    pub fn target(&self) -> Option<(usize, usize)> {
+        self.target.as_ref().map(|coord| (coord.y, coord.x))
+    }
+}
+
+/// Tiles of robot's known map that were discovered or changed since the previous update, the whole map is never sent
+///
+#[derive(Debug, Clone)]
+pub struct RobotMapUpdate {
+    pub(super) world_dimension: usize,
+    pub(super) changed_tiles: Vec<(usize, usize, Tile)>   // (row, col, tile)
+}
+
+impl RobotMapUpdate {
+    /// Known tiles of `current_map` that are unknown or have different type or content in `previous_map`
+    pub(super) fn from_maps(previous_map: &Option<Vec<Vec<Option<Tile>>>>, current_map: &Vec<Vec<Option<Tile>>>) -> RobotMapUpdate {
+        let mut changed_tiles = Vec::new();
+        for (row, tiles) in current_map.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if let Some(tile) = tile {
+                    let previous_tile = previous_map.as_ref().and_then(|map| map[row][col].as_ref());
+                    let changed = match previous_tile {
+                        Some(previous_tile) => previous_tile.tile_type != tile.tile_type || previous_tile.content != tile.content,
+                        None => true,
+                    };
+                    if changed {
+                        changed_tiles.push((row, col, tile.clone()));
+                    }
+                }
+            }
+        }
+        RobotMapUpdate { world_dimension: current_map.len(), changed_tiles }
+    }
+
+    pub(super) fn apply(&self, robot_map: &mut Option<Vec<Vec<Option<Tile>>>>) {
+        let dimension = self.world_dimension;
+        let robot_map = robot_map.get_or_insert_with(|| vec![vec![None; dimension]; dimension]);
+        for (row, col, tile) in self.changed_tiles.iter() {
+            robot_map[*row][*col] = Some(tile.clone());
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.changed_tiles.is_empty()
+    }
+
+    pub fn world_dimension(&self) -> usize {
+        self.world_dimension
+    }
+
+    /// Changed tiles as (row, col, tile)
+    pub fn changed_tiles(&self) -> &[(usize, usize, Tile)] {
+        &self.changed_tiles
+    }
+}
+
+/// How the interface call ended, recorded by `VisualizableInterfaces` after the interface returns
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InterfaceOutcome {
+    error: Option<String>,          // `LibError` variant or error of a traced tool, None when the call succeeded
+    energy_cost: i64,               // energy robot had before the call minus energy after it, negative when the call recharged the robot
+    returned_amount: Option<usize>  // quantity destroyed or put, number of discovered tiles
+}
+
+impl InterfaceOutcome {
+    pub(super) fn new(error: Option<String>, energy_cost: i64, returned_amount: Option<usize>) -> InterfaceOutcome {
+        InterfaceOutcome { error, energy_cost, returned_amount }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn energy_cost(&self) -> i64 {
+        self.energy_cost
+    }
+
+    pub fn returned_amount(&self) -> Option<usize> {
+        self.returned_amount
+    }
 }
 
 #[derive(Debug)]
-pub(super) struct InterfaceInvocation{
+pub struct InterfaceInvocation{
     pub(super) interface_action: Action,
-    pub(super) robot_position: Coord,
-    pub(super) riz_message: Option<String>
+    pub(super) robot_position: Coord,          // x is the column, same as everywhere in the visualizer
+    pub(super) riz_message: Option<String>,
+    pub(super) outcome: InterfaceOutcome,
+    pub(super) during_event_handling: bool     // published from robot's `handle_event`, in the middle of the call that raised the event
 }
 
 impl InterfaceInvocation {
-    pub(crate) fn new(interface_action: Action, robot_position: Coord, riz_message: Option<String>) -> InterfaceInvocation {
+    pub(crate) fn new(interface_action: Action, robot_position: Coord, riz_message: Option<String>, outcome: InterfaceOutcome) -> InterfaceInvocation {
         InterfaceInvocation {
             interface_action, 
             robot_position,
-            riz_message
+            riz_message,
+            outcome,
+            during_event_handling: false
         }
     }
+
+    pub fn action(&self) -> &Action {
+        &self.interface_action
+    }
+
+    /// Robot position as (row, col) at the time the interface was invoked
+    pub fn robot_position(&self) -> (usize, usize) {
+        (self.robot_position.y, self.robot_position.x)
+    }
+
+    pub fn riz_message(&self) -> Option<&str> {
+        self.riz_message.as_deref()
+    }
+
+    pub fn outcome(&self) -> &InterfaceOutcome {
+        &self.outcome
+    }
+}
+
+// `Action` from history cache does not implement `Clone`, it is copied via its serializable mirror
+impl Clone for InterfaceInvocation {
+    fn clone(&self) -> Self {
+        InterfaceInvocation {
+            interface_action: SerializableAction::from(&self.interface_action).into(),
+            robot_position: self.robot_position.clone(),
+            riz_message: self.riz_message.clone(),
+            outcome: self.outcome.clone(),
+            during_event_handling: self.during_event_handling
+        }
+    }
+}
+
+#[derive(Default)]
+struct ListenerState {
+    sinks: Vec<Box<dyn EventSink>>,
+    forwarded_event: Option<Discriminant<RobotEvent>>, // kind of the event being forwarded by `EventForwardingRobot`, taken by the first robot's call of `handle_event` for it
+    forwarding: bool                                    // robot's `handle_event` is running, i.e. the call that raised the event is still in flight
 }
 
+/// Passes everything robot publishes to its event sinks. Listener created by the visualizer sends the data to the visualizer
+/// and plays sounds, listener created by `headless` has no sinks until they are added by `with_sink`.
+/// Clones share the sinks, so that `EventForwardingRobot` can publish events on behalf of the robot.
+///
+#[derive(Clone)]
 pub struct VisualizerEventListener{
-    pub(super) sender: Sender<ChannelItem>,
-    audio_tool: Option<OxAgAudioTool>,
+    state: Rc<RefCell<ListenerState>>,
 }
 
 impl VisualizerEventListener {
-    pub(super) fn new(sender: Sender<ChannelItem>, use_sound: bool) -> VisualizerEventListener {
-        VisualizerEventListener{
-            sender,
-            audio_tool: if use_sound { Some(VisualizerEventListener::get_configured_audio_tool())} else {None},
+    /// Sounds are played only when `sound_settings` are given
+    pub(super) fn new(sender: Sender<ChannelItem>, sound_settings: Option<&VisualizerSettings>) -> VisualizerEventListener {
+        let mut listener = VisualizerEventListener::headless().with_sink(ChannelSink::new(sender));
+        if let Some(settings) = sound_settings {
+            match AudioSink::new(settings) {
+                Ok(audio_sink) => listener.add_sink(Box::new(audio_sink)),
+                Err(err) => panic!("{:?}", err),
+            }
         }
+        listener
+    }
+
+    /// Listener without any sink, robot can be run without the visualizer
+    pub fn headless() -> VisualizerEventListener {
+        VisualizerEventListener { state: Rc::new(RefCell::new(ListenerState::default())) }
+    }
+
+    pub fn with_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.add_sink(Box::new(sink));
+        self
     }
 
+    pub fn add_sink(&mut self, sink: Box<dyn EventSink>) {
+        self.state.borrow_mut().sinks.push(sink);
+    }
+
+    /// Kept for robots forwarding every event they receive. Events sent by the runner are published automatically when the robot runs
+    /// wrapped in `EventForwardingRobot` (the visualizer always wraps it), so the first call for an event of the same kind as the one
+    /// being forwarded is ignored and robots calling this from their `handle_event` do not publish events twice.
+    /// Events the robot creates itself are published by `publish_event`.
     pub fn handle_event(&mut self, event: &RobotEvent) {
-        self.send_event(event.clone());
-        self.play_audio_based_on_event(event);
+        let is_forwarded = {
+            let mut state = self.state.borrow_mut();
+            let is_forwarded = state.forwarded_event == Some(mem::discriminant(event));
+            if is_forwarded {
+                state.forwarded_event = None;
+            }
+            is_forwarded
+        };
+        if !is_forwarded {
+            self.publish_event(event);
+        }
+    }
+
+    pub(super) fn begin_forwarded_event(&self, event: &RobotEvent) {
+        self.publish_event(event);
+        let mut state = self.state.borrow_mut();
+        state.forwarded_event = Some(mem::discriminant(event));
+        state.forwarding = true;
+    }
+
+    pub(super) fn end_forwarded_event(&self) {
+        let mut state = self.state.borrow_mut();
+        state.forwarded_event = None;
+        state.forwarding = false;
+    }
+
+    pub(super) fn is_forwarding_event(&self) -> bool {
+        self.state.borrow().forwarding
     }
 
-    fn send_event(&self, event: RobotEvent) {
-        println_d!("DATA SENDER sending event: {:?}", event);
-        let channel_item = ChannelItem::EventChannelItem(event.clone());
-        self.sender.send(channel_item).expect(&format!("VisualizerDataSender: sending event {} failed.", event));
+    /// Publishes event the robot creates itself, e.g. `EnergyRecharged` after recharging by a tool, runner does not know about such events
+    pub fn publish_event(&self, event: &RobotEvent) {
+        println_d!("DATA SENDER publishing event: {:?}", event);
+        self.publish(ChannelItem::EventChannelItem(event.clone()));
     }
 
-    fn play_audio_based_on_event(&mut self, event: &RobotEvent) {
-        if let Some(audio_tool) = &mut self.audio_tool {
-            let audio_res = audio_tool.play_audio_based_on_event(&event);
-            match audio_res {
-                Ok(_) => {},
-                Err(err) => println!("Audio tool error: {}", err),
+    /// Publishes path robot is planning to walk so that it can be displayed over the tile grid.
+    /// Coordinates are given as (row, col) the same way as robotic lib `Coordinate`, `target` is the tile robot is heading to.
+    pub fn publish_plan(&self, path: &[(usize, usize)], target: Option<(usize, usize)>) {
+        let plan = RobotPlan {
+            path: path.iter().map(|(row, col)| Coord::new(*col, *row)).collect(),
+            target: target.map(|(row, col)| Coord::new(col, row))
+        };
+        self.send_plan(plan);
+    }
+
+    /// Same as `publish_plan`, path is given as directions starting from `robot_position` (row, col), e.g. as returned by `CollectTool::return_path_to_coordinates`.
+    /// Last tile of the path is used as the target.
+    pub fn publish_directions_plan(&self, robot_position: (usize, usize), directions: &[Direction]) {
+        let (mut row, mut col) = robot_position;
+        let mut path = vec![(row, col)];
+        for direction in directions {
+            match direction {
+                Direction::Up => row = row.saturating_sub(1),
+                Direction::Down => row += 1,
+                Direction::Left => col = col.saturating_sub(1),
+                Direction::Right => col += 1,
             }
+            path.push((row, col));
         }
+        let target = if directions.is_empty() { None } else { Some((row, col)) };
+        self.publish_plan(&path, target);
     }
 
-    fn get_configured_audio_tool() -> OxAgAudioTool {
-        let audio_res = get_configured_audio_tool();
-        match audio_res {
-            Ok(tool) => tool,
-            Err(err) => panic!("Audio tool error: {}", err),
+    /// Removes previously published plan from the visualization
+    pub fn clear_plan(&self) {
+        self.send_plan(RobotPlan::default());
+    }
+
+    /// Publishes free text note, e.g. why robot chose its next target. Annotations are written to the event log and recordings.
+    pub fn annotate(&self, annotation: &str) {
+        println_d!("DATA SENDER publishing annotation: {}", annotation);
+        self.publish(ChannelItem::AnnotationChannelItem(annotation.to_owned()));
+    }
+
+    fn send_plan(&self, plan: RobotPlan) {
+        println_d!("DATA SENDER publishing plan: {:?}", plan);
+        self.publish(ChannelItem::PlanChannelItem(plan));
+    }
+
+    pub(super) fn publish(&self, item: ChannelItem) {
+        for sink in self.state.borrow_mut().sinks.iter_mut() {
+            sink.consume(&item);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,207 @@
+use robotics_lib::world::tile::Tile;
+
+/// Single reversible change of a tile in one of the maps known to visualizer
+///
+enum MapChange {
+    WorldTile { x: usize, y: usize, old: Tile, new: Tile },
+    RobotTile { x: usize, y: usize, old: Option<Tile>, new: Option<Tile> }
+}
+
+/// State of the world after given tick, `map_changes` lead from the previous tick to this one
+///
+struct TickRecord<S> {
+    state: S,
+    map_changes: Vec<MapChange>
+}
+
+/// Keeps history of the world known to visualizer so that it is possible to step back through ticks.
+/// Maps are not copied every tick, only their changes are stored and undone/redone when moving in history.
+/// Everything else is stored as a snapshot of type `S`.
+///
+pub(super) struct WorldHistory<S: Clone> {
+    records: Vec<TickRecord<S>>,            // records[i] holds state after tick i + 1
+    pending_map_changes: Vec<MapChange>,    // changes done during tick that is in progress
+    displayed_tick: Option<usize>           // None when the live state is displayed
+}
+
+impl<S: Clone> WorldHistory<S> {
+    pub(super) fn new() -> WorldHistory<S> {
+        WorldHistory { records: Vec::new(), pending_map_changes: Vec::new(), displayed_tick: None }
+    }
+
+    pub(super) fn is_live(&self) -> bool {
+        self.displayed_tick.is_none()
+    }
+
+    /// Tick currently displayed, either historical or the live one
+    pub(super) fn displayed_tick(&self) -> usize {
+        self.displayed_tick.unwrap_or(self.latest_tick())
+    }
+
+    pub(super) fn latest_tick(&self) -> usize {
+        if self.is_live() { self.records.len() + 1 } else { self.records.len() }
+    }
+
+    pub(super) fn record_world_tile_change(&mut self, x: usize, y: usize, old: Tile, new: Tile) {
+        self.pending_map_changes.push(MapChange::WorldTile { x, y, old, new });
+    }
+
+    pub(super) fn record_robot_tile_change(&mut self, x: usize, y: usize, old: Option<Tile>, new: Option<Tile>) {
+        self.pending_map_changes.push(MapChange::RobotTile { x, y, old, new });
+    }
+
+    /// Closes the live tick, must be called before the next tick is simulated
+    pub(super) fn commit_tick(&mut self, state: S) {
+        let map_changes = std::mem::take(&mut self.pending_map_changes);
+        self.records.push(TickRecord { state, map_changes });
+    }
+
+    /// Moves maps to the state after `target_tick` and returns the snapshot that should be restored.
+    /// `live_state` must be provided when the live state is currently displayed, it is returned once the history reaches the live tick again.
+    pub(super) fn go_to_tick(&mut self, target_tick: usize, live_state: Option<S>, world_map: &mut Vec<Vec<Tile>>, robot_map: &mut Option<Vec<Vec<Option<Tile>>>>) -> Option<S> {
+        let mut current_tick = match self.displayed_tick {
+            Some(tick) => tick,
+            None => {
+                // freeze live tick so that it can be returned to
+                let live_state = live_state?;
+                self.commit_tick(live_state);
+                self.records.len()
+            }
+        };
+
+        let latest_tick = self.records.len();
+        let target_tick = target_tick.clamp(1, latest_tick);
+        while current_tick > target_tick {
+            self.undo(current_tick, world_map, robot_map);
+            current_tick -= 1;
+        }
+        while current_tick < target_tick {
+            current_tick += 1;
+            self.redo(current_tick, world_map, robot_map);
+        }
+
+        if target_tick == latest_tick {
+            // back to live, the tick may still be in progress
+            let live_record = self.records.pop()?;
+            self.pending_map_changes = live_record.map_changes;
+            self.displayed_tick = None;
+            return Some(live_record.state);
+        }
+        self.displayed_tick = Some(target_tick);
+        Some(self.records[target_tick - 1].state.clone())
+    }
+
+    fn undo(&self, tick: usize, world_map: &mut Vec<Vec<Tile>>, robot_map: &mut Option<Vec<Vec<Option<Tile>>>>) {
+        for change in self.records[tick - 1].map_changes.iter().rev() {
+            match change {
+                MapChange::WorldTile { x, y, old, .. } => world_map[*y][*x] = old.clone(),
+                MapChange::RobotTile { x, y, old, .. } => set_robot_tile(robot_map, world_map.len(), *x, *y, old.clone()),
+            }
+        }
+    }
+
+    fn redo(&self, tick: usize, world_map: &mut Vec<Vec<Tile>>, robot_map: &mut Option<Vec<Vec<Option<Tile>>>>) {
+        for change in self.records[tick - 1].map_changes.iter() {
+            match change {
+                MapChange::WorldTile { x, y, new, .. } => world_map[*y][*x] = new.clone(),
+                MapChange::RobotTile { x, y, new, .. } => set_robot_tile(robot_map, world_map.len(), *x, *y, new.clone()),
+            }
+        }
+    }
+}
+
+fn set_robot_tile(robot_map: &mut Option<Vec<Vec<Option<Tile>>>>, world_dimension: usize, x: usize, y: usize, tile: Option<Tile>) {
+    let robot_map = robot_map.get_or_insert_with(|| vec![vec![None; world_dimension]; world_dimension]);
+    robot_map[y][x] = tile;
+}
+
+#[cfg(test)]
+mod tests {
+    use robotics_lib::world::tile::{Content, Tile, TileType};
+
+    use super::WorldHistory;
+
+    fn tile(elevation: usize) -> Tile {
+        Tile { tile_type: TileType::Grass, content: Content::None, elevation }
+    }
+
+    /// Changes the world tile the same way as the visualizer does and records the change
+    fn change_world_tile(history: &mut WorldHistory<usize>, world_map: &mut Vec<Vec<Tile>>, x: usize, y: usize, elevation: usize) {
+        let old = world_map[y][x].clone();
+        world_map[y][x] = tile(elevation);
+        history.record_world_tile_change(x, y, old, tile(elevation));
+    }
+
+    fn elevations(world_map: &Vec<Vec<Tile>>) -> Vec<Vec<usize>> {
+        world_map.iter().map(|row| row.iter().map(|tile| tile.elevation).collect()).collect()
+    }
+
+    #[test]
+    fn step_back_from_live_and_return() {
+        let mut history = WorldHistory::new();
+        let mut world_map = vec![vec![tile(0); 2]; 2];
+        let mut robot_map = None;
+        change_world_tile(&mut history, &mut world_map, 0, 0, 1);
+        history.commit_tick(1);
+        change_world_tile(&mut history, &mut world_map, 1, 0, 2);
+
+        assert_eq!(history.go_to_tick(1, Some(2), &mut world_map, &mut robot_map), Some(1));
+        assert!(!history.is_live());
+        assert_eq!(history.displayed_tick(), 1);
+        assert_eq!(elevations(&world_map), vec![vec![1, 0], vec![0, 0]]);
+
+        assert_eq!(history.go_to_tick(2, None, &mut world_map, &mut robot_map), Some(2));
+        assert!(history.is_live());
+        assert_eq!(history.displayed_tick(), 2);
+        assert_eq!(elevations(&world_map), vec![vec![1, 2], vec![0, 0]]);
+
+        // changes of the live tick are still undoable after returning to it
+        assert_eq!(history.go_to_tick(1, Some(2), &mut world_map, &mut robot_map), Some(1));
+        assert_eq!(elevations(&world_map), vec![vec![1, 0], vec![0, 0]]);
+    }
+
+    #[test]
+    fn jump_past_both_ends_is_clamped() {
+        let mut history = WorldHistory::new();
+        let mut world_map = vec![vec![tile(0); 2]; 2];
+        let mut robot_map = None;
+        for tick in 1..=3 {
+            change_world_tile(&mut history, &mut world_map, 0, 0, tick);
+            history.commit_tick(tick);
+        }
+        change_world_tile(&mut history, &mut world_map, 0, 0, 4);
+
+        assert_eq!(history.go_to_tick(0, Some(4), &mut world_map, &mut robot_map), Some(1));
+        assert_eq!(history.displayed_tick(), 1);
+        assert_eq!(world_map[0][0].elevation, 1);
+
+        assert_eq!(history.go_to_tick(100, None, &mut world_map, &mut robot_map), Some(4));
+        assert!(history.is_live());
+        assert_eq!(history.latest_tick(), 4);
+        assert_eq!(world_map[0][0].elevation, 4);
+    }
+
+    #[test]
+    fn several_changes_of_one_tile_in_one_tick_round_trip() {
+        let mut history = WorldHistory::new();
+        let mut world_map = vec![vec![tile(0); 2]; 2];
+        history.commit_tick(1);
+        for elevation in 1..=3 {
+            change_world_tile(&mut history, &mut world_map, 1, 1, elevation);
+        }
+        history.record_robot_tile_change(1, 1, None, Some(tile(1)));
+        history.record_robot_tile_change(1, 1, Some(tile(1)), Some(tile(3)));
+        let mut robot_map = Some(vec![vec![None, None], vec![None, Some(tile(3))]]);
+        history.commit_tick(2);
+
+        for _ in 0..2 {
+            history.go_to_tick(1, Some(3), &mut world_map, &mut robot_map);
+            assert_eq!(world_map[1][1].elevation, 0);
+            assert!(robot_map.as_ref().unwrap()[1][1].is_none());
+
+            history.go_to_tick(2, None, &mut world_map, &mut robot_map);
+            assert_eq!(world_map[1][1].elevation, 3);
+            assert_eq!(robot_map.as_ref().unwrap()[1][1].as_ref().map(|tile| tile.elevation), Some(3));
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use robotics_lib::{runner::{Robot, Runnable}, world::tile::{Content, Tile}};
+use robotics_lib::{runner::{Robot, Runnable}, world::tile::Content};
 use robotics_lib::interface::{Direction};
-use crate::{oh_crab_visualizer::visualizer::{visualizable_interfaces::VisualizableInterfaces, visualizable_robot::{RobotCreator, Visulizable}, visualizer_event_listener::VisualizerEventListener}, println_d};
+use crate::{oh_crab_visualizer::visualizer::{visualizable_interfaces::VisualizableInterfaces, visualizable_robot::RobotCreator, visualizer_event_listener::VisualizerEventListener}, println_d, visulizable};
 use crate::robot_veronika::partitioning::PartitioningProblem;
 use crate::robot_veronika::content_pick::collect::{CollectTool, LibErrorExtended};
 use crate::robot_veronika::storage::{StorageInfo, Position};
@@ -49,10 +49,9 @@ impl DistributorRobot{
         for i in 0..furthest_top_coordinates.len() {
             let coordinate_robot: (usize, usize) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
             let coordinate_test: (usize, usize) = (coordinate_robot.0  - (view_output.len()), coordinate_robot.1 - i);
-            println!("Testing coordinates {:?}, UP", coordinate_test);
+            println_d!("Testing coordinates {:?}, UP", coordinate_test);
             //let path = rust_eze_tomtom::TomTom::get_path_to_coordinates(self, world, false, coordinate_test);
             let path = CollectTool::return_path_to_coordinates(self, world, coordinate_test);
-            println!("Got over path");
             if path.is_ok(){
                 //at this point, we were able to get to the top
                 top_coordinates = Some(coordinate_test);
@@ -70,7 +69,7 @@ impl DistributorRobot{
         for i in 0..furthest_bottom_coordinates.len() {
             let coordinate_robot: (usize, usize) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
             let coordinate_test: (usize, usize) = (coordinate_robot.0  + (view_output.len()), coordinate_robot.1 - i);
-            println!("Testing coordinates {:?}, DOWN", coordinate_test);
+            println_d!("Testing coordinates {:?}, DOWN", coordinate_test);
             //let path = rust_eze_tomtom::TomTom::get_path_to_coordinates(self, world, false, coordinate_test);
             let path = CollectTool::return_path_to_coordinates(self, world, coordinate_test);
             if path.is_ok(){
@@ -83,7 +82,7 @@ impl DistributorRobot{
         }
 
         if top_coordinates.is_some() && bottom_coordinates.is_some() {
-            println!("Top and bottom are connected, we can walk up or down to look around");
+            self.visualizer_event_listener.annotate("Top and bottom are connected, I can walk up or down to look around");
             let top_coordinates = top_coordinates.unwrap();
             let bottom_coordinates = bottom_coordinates.unwrap();
 
@@ -145,8 +144,7 @@ impl DistributorRobot{
                 let second_index = &view_output[i].len() - 1;
                 let coordinate_robot: (usize, usize) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
                 let coordinate_test: (usize, usize) = (coordinate_robot.0  - i, coordinate_robot.1 - (second_index));
-                println!("Testing coordinates {:?}, LEFT", coordinate_test);
-                println!("Robot's position {:?}", coordinate_robot);
+                println_d!("Testing coordinates {:?}, LEFT, robot's position {:?}", coordinate_test, coordinate_robot);
                 //let path = rust_eze_tomtom::TomTom::get_path_to_coordinates(self, world, false, coordinate_test);
                 let path = CollectTool::return_path_to_coordinates(self, world, coordinate_test);
                 if path.is_ok(){
@@ -165,7 +163,7 @@ impl DistributorRobot{
                 let second_index = &view_output[i].len() - 1;
                 let coordinate_robot: (usize, usize) = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
                 let coordinate_test: (usize, usize) = (coordinate_robot.0  - i, coordinate_robot.1 + (second_index));
-                println!("Testing coordinates {:?}, RIGHT", coordinate_test);
+                println_d!("Testing coordinates {:?}, RIGHT", coordinate_test);
                 //let path = rust_eze_tomtom::TomTom::get_path_to_coordinates(self, world, false, coordinate_test);
                 let path = CollectTool::return_path_to_coordinates(self, world, coordinate_test);
                 if path.is_ok(){
@@ -229,7 +227,7 @@ impl DistributorRobot{
         let portion_explored = self.get_quantity_explored_world(world);
         if portion_explored > 0.99{
             self.exploration_finished = true;
-            println!("Portion explored is {}", portion_explored);
+            self.visualizer_event_listener.annotate(&format!("Exploration finished, portion explored is {}", portion_explored));
             return Ok(());
         }
         Err(LibError::OutOfBounds)
@@ -248,7 +246,7 @@ impl DistributorRobot{
             5
         );
         let best_solution: Vec<usize> = evolutionary_algo.main_exec("logs/market_distribution.log");
-        println!("Best solution is {:?}", best_solution);
+        self.visualizer_event_listener.annotate(&format!("Best solution is {:?}", best_solution));
         self.partitioning_solved = true;
 
         let mut new_targets = BinaryHeap::new();
@@ -263,7 +261,7 @@ impl DistributorRobot{
 
     pub fn deliver_content(&mut self, world: &mut robotics_lib::world::World)-> Result<(), LibErrorExtended>{
         while let Some(target) = self.targets.pop() {
-            println!("I am distributing the content!");
+            self.visualizer_event_listener.annotate("I am distributing the content!");
             //let market_index: usize = self.markets_indexes.pop_front().unwrap();
             // go to collect the item first
 
@@ -272,6 +270,7 @@ impl DistributorRobot{
                                                                              (target.get_position().get_row(),
                                                                               target.get_position().get_col()))?;
 
+            self.publish_plan(&path_to_target);
             let last_step = path_to_target.pop();
             for direction in path_to_target {
                 let _ = VisualizableInterfaces::go(self, world, direction);
@@ -282,6 +281,7 @@ impl DistributorRobot{
                                                                              world,
                                                                              (maker_position.get_row(),
                                                                               maker_position.get_col()))?;
+            self.publish_plan(&path_to_market);
             let last_step = path_to_market.pop();
             for direction in path_to_market {
                 let _ = VisualizableInterfaces::go(self, world, direction);
@@ -306,6 +306,7 @@ impl DistributorRobot{
                                                                            world,
                                                                            (closest_bank.get_row(),
                                                                             closest_bank.get_col()))?;
+            self.publish_plan(&path_to_bank);
             let last_step = path_to_bank.pop();
             for direction in path_to_bank {
                 let _ = VisualizableInterfaces::go(self, world, direction);
@@ -323,6 +324,11 @@ impl DistributorRobot{
         return Ok(());
     }
 
+    fn publish_plan(&self, path: &Vec<Direction>) {
+        let robot_position = (self.get_coordinate().get_row(), self.get_coordinate().get_col());
+        self.visualizer_event_listener.publish_directions_plan(robot_position, path);
+    }
+
     pub fn get_quantity_explored_world(&mut self, world: &mut robotics_lib::world::World) -> f32 {
         let robot_world = VisualizableInterfaces::robot_map(self, world).unwrap();
         let number_of_tiles: usize = robot_world.len() * robot_world.len();
@@ -362,23 +368,6 @@ impl DistributorRobot{
         return (non_none_tiles_counter as f32) / (number_of_tiles as f32);
     }
 
-    #[allow(dead_code)]
-    fn print_nicer_known_world_map(&self, known_world: &Vec<Vec<Option<Tile>>>) {
-        for row in known_world {
-            for col in row {
-                if col.is_none() {
-                    print!("None\t");
-                    continue;
-                }
-                let tile = col.clone().unwrap();
-                print!("Type {:?},", tile.tile_type);
-                print!("{:?} ", tile.content);
-                //print!("(cost {:?})\t", tile.tile_type.properties().cost());
-            }
-            println!();
-        }
-    }
-
     fn extract_storage_into_weights(&self) -> Vec<u32>{
         let mut weights = Vec::with_capacity(self.targets.len());
         let mut targets = self.targets.clone();
@@ -437,27 +426,21 @@ impl RobotCreator for DistributorRobotFactory {
     }
 }
 
-impl<'a> Visulizable<'a> for DistributorRobot {
-    fn borrow_event_listener(&'a self) -> &'a VisualizerEventListener{
-        &self.visualizer_event_listener
-    }
-}
+visulizable!(DistributorRobot, visualizer_event_listener);
 
 impl Runnable for DistributorRobot{
     fn process_tick(&mut self, world: &mut robotics_lib::world::World) {
         self.tick_counter+=1;
-        println!("CURRENT TICK is {}", self.tick_counter);
-        println!("CURRENT SCORE IS {}", VisualizableInterfaces::get_score(self, world));
-        println!("Robot's position {:?}", self.robot.coordinate);
+        println_d!("CURRENT TICK is {}, robot's position {:?}", self.tick_counter, self.robot.coordinate);
 
         ////// EXPLORATION PHASE
         if self.exploration_finished == false {
             let exploration_output = self.exploration_phase(world);
             if exploration_output.is_err(){
-                println!("Exploration didn't go well, I choose random direction");
+                self.visualizer_event_listener.annotate("Exploration didn't go well, I choose random direction");
                 // rand index generation
                 let random_index = rand::random::<usize>() % Direction::iter().len();
-                println!("Random index is {}", random_index);
+                println_d!("Random index is {}", random_index);
                 let direction = match random_index {
                     0 => Direction::Down,
                     1 => Direction::Up,
@@ -471,10 +454,10 @@ impl Runnable for DistributorRobot{
         ////// PARTITIONING PROBLEM SOLUTION PHASE
         else if self.partitioning_solved == false{
             if self.markets.len() < 1 || self.banks.len() < 1{
-                println!("I have nothing to do in the world, banks or markets are missing.")
+                self.visualizer_event_listener.annotate("I have nothing to do in the world, banks or markets are missing.")
             }
             else{
-                println!("I am solving partitioning problem with an evolutionary algorithm!");
+                self.visualizer_event_listener.annotate("I am solving partitioning problem with an evolutionary algorithm!");
                 let _ = self.solve_packaging_problem();
             }
         }
@@ -482,25 +465,24 @@ impl Runnable for DistributorRobot{
         else{
                 let output = self.deliver_content(world);
                 if output.is_err(){
-                    println!("Something went wrong with distribution");
+                    self.visualizer_event_listener.annotate("Something went wrong with distribution");
                 }
         }
 
         // check if there are no more targets
         if self.targets.len() == 0{
-            println!("I am out of targets, everything is delivered.");
+            self.visualizer_event_listener.annotate("I am out of targets, everything is delivered.");
         }
     }
 
-    fn handle_event(&mut self, event: robotics_lib::event::events::Event) {
-        println_d!("Example robot received event: {}", event);
-        // BEWARE - for a visualizer to work it is necessary to call this method from
-        // handle_event method of your robot
-        self.visualizer_event_listener.handle_event(&event);
+    fn handle_event(&mut self, _event: robotics_lib::event::events::Event) {
+        // events are forwarded to the visualizer automatically by `EventForwardingRobot`
         if self.get_energy().get_energy_level() < 300 {
             let previous_energy = self.get_energy().get_energy_level();
-            *self.get_energy_mut()=Dynamo::update_energy();
-            self.handle_event(EnergyRecharged(1000-previous_energy));
+            VisualizableInterfaces::trace_tool(self, "Dynamo recharge", Some("My energy is low, Dynamo recharges me!".to_owned()),
+                |robot| *robot.get_energy_mut()=Dynamo::update_energy());
+            // runner does not know about the recharge, the event has to be published by the robot
+            self.visualizer_event_listener.publish_event(&EnergyRecharged(1000-previous_energy));
         }
     }
 
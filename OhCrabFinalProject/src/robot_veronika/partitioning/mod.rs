@@ -48,6 +48,11 @@ impl PartitioningProblem {
         }
     }
 
+    /// Problem with the parameters used for the standalone partitioning run.
+    pub fn with_default_params(weights: Vec<u32>, piles: usize) -> Self {
+        PartitioningProblem::new(weights, piles, 100, 1500, 0.8, 0.22, 0.085, 10)
+    }
+
     pub fn set_weights(&mut self, weights: Vec<u32>) {
         self.weights = weights;
     }
@@ -234,16 +239,7 @@ impl PartitioningProblem {
 }
 
 pub fn create_eva_problem(){
-    let mut problem = PartitioningProblem::new(
-        Vec::new(),
-        10,
-        100,
-        1500,
-        0.8,
-        0.22,
-        0.085,
-        10
-    );
+    let mut problem = PartitioningProblem::with_default_params(Vec::new(), 10);
     problem.set_weights_from_file("test_data/partition.txt");
     println!("Weights successfully loaded");
     let _best_solution: Vec<usize> = problem.main_exec("logs/evolutionary_algo_test.log");
@@ -8,7 +8,13 @@ pub fn run_distribution_robot(){
 
     //let config = OhCrabVisualizerConfig::new(RunMode::Interactive, false);
     let config = OhCrabVisualizerConfig::new(RunMode::NonInteractive(500), false);
-    let mut visualizer = OhCrabVisualizer::new(robot_factory, world_generator, config);
+    let mut visualizer = match OhCrabVisualizer::new(robot_factory, world_generator, config) {
+        Ok(visualizer) => visualizer,
+        Err(err) => {
+            println!("Creating visualizer returned error {:?}", err);
+            return;
+        }
+    };
 
     visualizer.simulate().unwrap();
     // match visualizer.run() {
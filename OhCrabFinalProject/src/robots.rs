@@ -0,0 +1,41 @@
+use robotics_lib::world::tile::Content;
+
+use crate::{oh_crab_visualizer::{examples::example_robot::ExampleRobotFactory, visualizer::{robot_registry::RobotRegistry, visualizer::OhCrabVisualizerError}},
+            robot_veronika::distribution_robot::DistributorRobotFactory};
+
+/// Registry of all robots of this crate, new robots are registered here to be runnable by name from the binary
+pub fn builtin_robot_registry() -> RobotRegistry {
+    RobotRegistry::new()
+        .register("distributor", "Collects desired content and distributes it evenly into markets",
+            &[("content", "comma separated contents to collect, default rock,fish,tree")],
+            |params| {
+                let desired_content = params.get_list_or("content", &["rock", "fish", "tree"]).iter()
+                    .map(|content_name| parse_content(content_name).map(|content| content.index()))
+                    .collect::<Result<Vec<usize>, OhCrabVisualizerError>>()?;
+                Ok(Box::new(DistributorRobotFactory::new(desired_content)))
+            })
+        .register("example", "Example robot showing how to use the visualizer",
+            &[("some_param", "integer passed to the robot, default 42")],
+            |params| Ok(Box::new(ExampleRobotFactory::new(params.get_or("some_param", 42)?))))
+}
+
+fn parse_content(content_name: &str) -> Result<Content, OhCrabVisualizerError> {
+    match content_name.to_lowercase().replace(['-', '_'], "").as_str() {
+        "rock" => Ok(Content::Rock(0)),
+        "tree" => Ok(Content::Tree(0)),
+        "garbage" => Ok(Content::Garbage(0)),
+        "fire" => Ok(Content::Fire),
+        "coin" => Ok(Content::Coin(0)),
+        "bin" => Ok(Content::Bin(0..0)),
+        "crate" => Ok(Content::Crate(0..0)),
+        "bank" => Ok(Content::Bank(0..0)),
+        "water" => Ok(Content::Water(0)),
+        "market" => Ok(Content::Market(0)),
+        "fish" => Ok(Content::Fish(0)),
+        "building" => Ok(Content::Building),
+        "bush" => Ok(Content::Bush(0)),
+        "jollyblock" => Ok(Content::JollyBlock(0)),
+        "scarecrow" => Ok(Content::Scarecrow),
+        _ => Err(OhCrabVisualizerError::ConfigurationError(format!("Unknown content {}", content_name)))
+    }
+}
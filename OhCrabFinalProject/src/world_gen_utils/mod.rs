@@ -3,6 +3,7 @@ use robotics_lib::world::tile::Content;
 use oxagworldgenerator::world_generator::content_options::OxAgContentOptions;
 use oxagworldgenerator::world_generator::tile_type_options::OxAgTileTypeOptions;
 use oxagworldgenerator::world_generator::{OxAgWorldGenerator, world_generator_builder::OxAgWorldGeneratorBuilder};
+use crate::oh_crab_visualizer::visualizer::visualizer::OhCrabVisualizerError;
 
 fn non_empty_file_exists(filename: &str) -> bool {
     if let Ok(metadata) = fs::metadata(filename) {
@@ -43,6 +44,14 @@ pub fn load_world(size: usize, seed: u64) -> OxAgWorldGenerator {
     generator
 }
 
+/// Loads world saved by `OxAgWorldGenerator::save` from any path, fails when the file cannot be read or is not a saved world
+pub fn load_world_from_file(path: &str) -> Result<OxAgWorldGenerator, OhCrabVisualizerError> {
+    println!("Loading world from {}", path);
+    OxAgWorldGeneratorBuilder::new()
+        .load(path)
+        .map_err(|err| OhCrabVisualizerError::ConfigurationError(format!("Loading world {} failed: {:?}", path, err)))
+}
+
 pub fn load_or_generate_world(size: usize, seed: u64) -> OxAgWorldGenerator {
     let path = build_path(size, seed);
     if non_empty_file_exists(&path) {